@@ -0,0 +1,124 @@
+//! A stand-in for a notification daemon, for trying yoinkctl's pick
+//! notifications without a desktop. Run both on a private session bus:
+//!
+//! ```bash
+//! dbus-run-session -- sh -c '
+//!     cargo run --example mock_notifications &
+//!     MOCK_PORTAL_DENY=Screenshot cargo run --example mock_portal &
+//!     sleep 1
+//!     YOINKCTL_BACKEND=portal cargo run -- pick'
+//! ```
+//!
+//! With the screenshot denied, `pick` falls back to the portal's `PickColor`,
+//! which needs no display. On a desktop, any pick works.
+//!
+//! - Every notification is printed with its actions and icon size
+//! - `MOCK_NOTIFY_ACTION=rgb|history` clicks that action after
+//!   `MOCK_NOTIFY_DELAY_MS` milliseconds (default 500)
+//! - Otherwise notifications expire after their timeout
+//! - `MOCK_NOTIFY_NO_ACTIONS=1` advertises a server without buttons
+
+#[cfg(target_os = "linux")]
+mod mock {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+    use zbus::zvariant::{OwnedValue, Structure};
+    use zbus::{fdo, interface, Connection};
+
+    pub const PATH: &str = "/org/freedesktop/Notifications";
+    const INTERFACE: &str = "org.freedesktop.Notifications";
+
+    pub struct Notifications;
+
+    #[interface(name = "org.freedesktop.Notifications")]
+    impl Notifications {
+        fn get_capabilities(&self) -> Vec<&str> {
+            if std::env::var_os("MOCK_NOTIFY_NO_ACTIONS").is_some() {
+                vec!["body"]
+            } else {
+                vec!["actions", "body"]
+            }
+        }
+
+        fn get_server_information(&self) -> (&str, &str, &str, &str) {
+            ("mock_notifications", "yoinkctl", "0", "1.2")
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        async fn notify(
+            &self,
+            #[zbus(connection)] conn: &Connection,
+            app_name: &str,
+            _replaces_id: u32,
+            _app_icon: &str,
+            summary: &str,
+            body: &str,
+            actions: Vec<String>,
+            hints: HashMap<String, OwnedValue>,
+            expire_timeout: i32,
+        ) -> fdo::Result<u32> {
+            static COUNTER: AtomicU32 = AtomicU32::new(1);
+            let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+            println!("🔔 #{} from {}: {}", id, app_name, summary);
+            for line in body.lines() {
+                println!("   {}", line);
+            }
+            let buttons: Vec<&str> = actions.chunks(2).filter_map(|pair| pair.get(1).map(String::as_str)).collect();
+            if !buttons.is_empty() {
+                println!("   [{}]", buttons.join("] ["));
+            }
+            if let Some(image) = hints.get("image-data").and_then(|value| Structure::try_from(value.try_clone().ok()?).ok()) {
+                if let [width, height, ..] = image.fields() {
+                    println!("   icon {}×{}", width, height);
+                }
+            }
+
+            let action = std::env::var("MOCK_NOTIFY_ACTION").ok().filter(|action| actions.contains(action));
+            let delay = match action {
+                Some(_) => std::env::var("MOCK_NOTIFY_DELAY_MS").ok().and_then(|ms| ms.parse().ok()).unwrap_or(500),
+                None => expire_timeout.max(0) as u64,
+            };
+            let signals = zbus::blocking::Connection::from(conn.clone());
+
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(delay));
+                if let Some(action) = &action {
+                    println!("👆 #{} {}", id, action);
+                    signals.emit_signal(None::<()>, PATH, INTERFACE, "ActionInvoked", &(id, action.as_str())).ok();
+                }
+                // 1 is "expired", 2 "dismissed by the user"
+                let reason = if action.is_some() { 2u32 } else { 1 };
+                println!("💤 #{} closed", id);
+                signals.emit_signal(None::<()>, PATH, INTERFACE, "NotificationClosed", &(id, reason)).ok();
+            });
+
+            Ok(id)
+        }
+
+        async fn close_notification(&self, #[zbus(connection)] conn: &Connection, id: u32) -> fdo::Result<()> {
+            println!("💤 #{} closed by the app", id);
+            conn.emit_signal(None::<()>, PATH, INTERFACE, "NotificationClosed", &(id, 3u32)).await?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn main() -> zbus::Result<()> {
+    let _conn = zbus::blocking::connection::Builder::session()?
+        .name("org.freedesktop.Notifications")?
+        .serve_at(mock::PATH, mock::Notifications)?
+        .build()?;
+
+    println!("🧪 Mock notification server running on the session bus");
+    loop {
+        std::thread::park();
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("The mock notification server needs D-Bus and only builds on Linux");
+}
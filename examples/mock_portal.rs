@@ -0,0 +1,203 @@
+//! A stand-in for xdg-desktop-portal, for trying yoinkctl's portal backend
+//! without a Wayland compositor. Run both on a private session bus:
+//!
+//! ```bash
+//! dbus-run-session -- sh -c '
+//!     cargo run --example mock_portal &
+//!     sleep 1
+//!     YOINKCTL_BACKEND=portal cargo run -- daemon'
+//! ```
+//!
+//! - `Screenshot` returns a generated gradient
+//! - `PickColor` returns `MOCK_PORTAL_COLOR` (`r,g,b` in 0..1, default `0.2,0.4,0.6`)
+//! - `GlobalShortcuts` accepts any binding and fires it every
+//!   `MOCK_PORTAL_ACTIVATE_SECS` seconds (default 5)
+//! - `MOCK_PORTAL_DENY=1` answers every request with "denied",
+//!   `MOCK_PORTAL_DENY=Screenshot` only screenshots, so picks go through `PickColor`
+//! - `MOCK_PORTAL_SILENT` takes the same values and never answers instead,
+//!   for trying the request timeouts
+
+#[cfg(target_os = "linux")]
+mod mock {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+    use zbus::message::Header;
+    use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+    use zbus::{fdo, interface, Connection};
+
+    pub const PATH: &str = "/org/freedesktop/portal/desktop";
+
+    type Options = HashMap<String, OwnedValue>;
+
+    fn sender(header: &Header<'_>) -> String {
+        header.sender()
+            .map(|name| name.trim_start_matches(':').replace('.', "_"))
+            .unwrap_or_default()
+    }
+
+    fn token(options: &Options, key: &str) -> String {
+        options.get(key)
+            .and_then(|value| String::try_from(value.try_clone().ok()?).ok())
+            .unwrap_or_else(|| "mock".to_string())
+    }
+
+    /// Answers a request the way the real portal does: through a
+    /// `Request::Response` signal on the handle derived from the caller's token
+    async fn respond(
+        conn: &Connection,
+        header: &Header<'_>,
+        options: &Options,
+        results: HashMap<&str, Value<'_>>,
+    ) -> fdo::Result<OwnedObjectPath> {
+        let handle = format!("{}/request/{}/{}", PATH, sender(header), token(options, "handle_token"));
+        let handle = OwnedObjectPath::try_from(handle).map_err(|e| fdo::Error::Failed(e.to_string()))?;
+
+        let applies = |variable: &str| match std::env::var(variable) {
+            Ok(method) if method != "1" => header.member().is_some_and(|member| member.as_str() == method),
+            Ok(_) => true,
+            Err(_) => false,
+        };
+        if applies("MOCK_PORTAL_SILENT") {
+            println!("🤐 Not answering {}", handle.as_str());
+            return Ok(handle);
+        }
+        let response: u32 = if applies("MOCK_PORTAL_DENY") { 2 } else { 0 };
+        conn.emit_signal(header.sender().map(|name| name.as_str()), &handle, "org.freedesktop.portal.Request", "Response", &(response, results))
+            .await?;
+
+        Ok(handle)
+    }
+
+    pub struct Screenshot;
+
+    #[interface(name = "org.freedesktop.portal.Screenshot")]
+    impl Screenshot {
+        async fn screenshot(
+            &self,
+            #[zbus(header)] header: Header<'_>,
+            #[zbus(connection)] conn: &Connection,
+            _parent_window: &str,
+            options: Options,
+        ) -> fdo::Result<OwnedObjectPath> {
+            static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+            let image = image::RgbaImage::from_fn(640, 480, |x, y| {
+                image::Rgba([(x * 255 / 640) as u8, (y * 255 / 480) as u8, 128, 255])
+            });
+            let path = std::env::temp_dir().join(format!("mock-portal-{}.png", COUNTER.fetch_add(1, Ordering::Relaxed)));
+            image.save(&path).map_err(|e| fdo::Error::Failed(e.to_string()))?;
+
+            let uri = url::Url::from_file_path(&path)
+                .map_err(|_| fdo::Error::Failed("Bad screenshot path".to_string()))?;
+            println!("📸 Screenshot -> {}", uri);
+
+            respond(conn, &header, &options, HashMap::from([("uri", Value::from(uri.to_string()))])).await
+        }
+
+        async fn pick_color(
+            &self,
+            #[zbus(header)] header: Header<'_>,
+            #[zbus(connection)] conn: &Connection,
+            _parent_window: &str,
+            options: Options,
+        ) -> fdo::Result<OwnedObjectPath> {
+            let color = std::env::var("MOCK_PORTAL_COLOR").unwrap_or_else(|_| "0.2,0.4,0.6".to_string());
+            let channels: Vec<f64> = color.split(',').filter_map(|c| c.trim().parse().ok()).collect();
+            let [r, g, b] = channels[..] else {
+                return Err(fdo::Error::InvalidArgs(format!("MOCK_PORTAL_COLOR '{}' is not r,g,b", color)));
+            };
+            println!("🎨 PickColor -> {}, {}, {}", r, g, b);
+
+            respond(conn, &header, &options, HashMap::from([("color", Value::from((r, g, b)))])).await
+        }
+
+        #[zbus(property, name = "version")]
+        fn version(&self) -> u32 {
+            2
+        }
+    }
+
+    pub struct GlobalShortcuts;
+
+    #[interface(name = "org.freedesktop.portal.GlobalShortcuts")]
+    impl GlobalShortcuts {
+        async fn create_session(
+            &self,
+            #[zbus(header)] header: Header<'_>,
+            #[zbus(connection)] conn: &Connection,
+            options: Options,
+        ) -> fdo::Result<OwnedObjectPath> {
+            let session = format!("{}/session/{}/{}", PATH, sender(&header), token(&options, "session_handle_token"));
+            println!("🔑 CreateSession -> {}", session);
+
+            respond(conn, &header, &options, HashMap::from([("session_handle", Value::from(session))])).await
+        }
+
+        async fn bind_shortcuts(
+            &self,
+            #[zbus(header)] header: Header<'_>,
+            #[zbus(connection)] conn: &Connection,
+            session_handle: ObjectPath<'_>,
+            shortcuts: Vec<(String, Options)>,
+            _parent_window: &str,
+            options: Options,
+        ) -> fdo::Result<OwnedObjectPath> {
+            let mut bound = Vec::new();
+            for (id, properties) in &shortcuts {
+                let trigger = token(properties, "preferred_trigger");
+                println!("⌨️  BindShortcuts {} -> {}", id, trigger);
+                bound.push((id.clone(), HashMap::from([("trigger_description", Value::from(trigger))])));
+            }
+
+            let interval = std::env::var("MOCK_PORTAL_ACTIVATE_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .unwrap_or(5);
+            let activate = zbus::blocking::Connection::from(conn.clone());
+            let session = OwnedObjectPath::from(session_handle.clone());
+            let ids: Vec<String> = shortcuts.into_iter().map(|(id, _)| id).collect();
+
+            std::thread::spawn(move || loop {
+                std::thread::sleep(Duration::from_secs(interval));
+                for id in &ids {
+                    println!("⚡ Activated {}", id);
+                    let timestamp = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_millis() as u64)
+                        .unwrap_or_default();
+                    let signal = (&session, id.as_str(), timestamp, HashMap::<&str, Value>::new());
+                    if activate.emit_signal(None::<()>, PATH, "org.freedesktop.portal.GlobalShortcuts", "Activated", &signal).is_err() {
+                        return;
+                    }
+                }
+            });
+
+            respond(conn, &header, &options, HashMap::from([("shortcuts", Value::from(bound))])).await
+        }
+
+        #[zbus(property, name = "version")]
+        fn version(&self) -> u32 {
+            1
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn main() -> zbus::Result<()> {
+    let _conn = zbus::blocking::connection::Builder::session()?
+        .name("org.freedesktop.portal.Desktop")?
+        .serve_at(mock::PATH, mock::Screenshot)?
+        .serve_at(mock::PATH, mock::GlobalShortcuts)?
+        .build()?;
+
+    println!("🧪 Mock portal running on the session bus");
+    loop {
+        std::thread::park();
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("The mock portal needs D-Bus and only builds on Linux");
+}
@@ -1,58 +1,586 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::fs;
 use std::env;
+use std::process::Command;
+
+use crate::config::Config;
+
+const SERVICE_NAME: &str = "yoinkctl.service";
+
+/// How the daemon gets launched at login
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutostartBackend {
+    /// `~/.config/autostart/yoinkctl.desktop`, started by XDG-compliant sessions
+    DesktopFile,
+    /// A `systemctl --user` unit bound to `graphical-session.target`
+    Systemd,
+}
+
+impl AutostartBackend {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::DesktopFile => "Desktop autostart entry",
+            Self::Systemd => "systemd user service",
+        }
+    }
+}
 
 pub struct Autostart {
     desktop_file_path: PathBuf,
+    unit_path: PathBuf,
 }
 
 impl Autostart {
     pub fn new() -> Self {
-        let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("~/.config"));
-        path.push("autostart");
+        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("~/.config"));
+
+        let mut path = config_dir.join("autostart");
         fs::create_dir_all(&path).ok();
         path.push("yoinkctl.desktop");
-        
+
         Self {
             desktop_file_path: path,
+            unit_path: config_dir.join("systemd").join("user").join(SERVICE_NAME),
         }
     }
-    
+
     pub fn is_enabled(&self) -> bool {
-        self.desktop_file_path.exists()
-    }
-    
-    pub fn enable(&self) -> Result<(), String> {
-        let exe_path = env::current_exe()
-            .map_err(|e| format!("Failed to get executable path: {}", e))?;
-        
-        let exe_path_str = exe_path.to_str()
-            .ok_or_else(|| "Invalid executable path".to_string())?;
-        
+        self.active_backend().is_some()
+    }
+
+    /// The backend currently set up to start the daemon, if any
+    pub fn active_backend(&self) -> Option<AutostartBackend> {
+        if self.systemd_enabled() {
+            Some(AutostartBackend::Systemd)
+        } else if self.desktop_file_path.exists() {
+            Some(AutostartBackend::DesktopFile)
+        } else {
+            None
+        }
+    }
+
+    /// Enables autostart through `backend`, removing the other one so the
+    /// daemon isn't launched twice
+    pub fn enable(&self, backend: AutostartBackend, config: &Config) -> Result<(), String> {
+        let command = LaunchCommand::from_config(config)?;
+
+        match backend {
+            AutostartBackend::DesktopFile => {
+                self.disable_systemd()?;
+                self.enable_desktop_file(&command)
+            }
+            AutostartBackend::Systemd => {
+                self.enable_systemd(&command)?;
+                self.disable_desktop_file()
+            }
+        }
+    }
+
+    /// Rewrites the active entry when the executable it launches is gone, e.g.
+    /// after moving the binary or rebooting with an entry that points into an
+    /// AppImage's temporary mount. Returns whether anything was repaired.
+    pub fn repair(&self, config: &Config) -> Result<bool, String> {
+        let Some(backend) = self.active_backend() else {
+            return Ok(false);
+        };
+
+        let path = match backend {
+            AutostartBackend::DesktopFile => &self.desktop_file_path,
+            AutostartBackend::Systemd => &self.unit_path,
+        };
+        let recorded = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| recorded_executable(&content, backend));
+
+        let appdir = env::var_os("APPDIR").map(PathBuf::from);
+        if recorded.as_deref().is_some_and(|path| !is_stale(path, appdir.as_deref())) {
+            return Ok(false);
+        }
+
+        self.enable(backend, config)?;
+        Ok(true)
+    }
+
+    pub fn disable(&self) -> Result<(), String> {
+        self.disable_systemd()?;
+        self.disable_desktop_file()
+    }
+
+    fn enable_desktop_file(&self, command: &LaunchCommand) -> Result<(), String> {
         let desktop_content = format!(
             "[Desktop Entry]
 Type=Application
 Name=yoinkctl
 Comment=Color picker daemon with global hotkey
-Exec={} daemon
+Exec={}
 Terminal=false
 Hidden=false
 X-GNOME-Autostart-enabled=true
 ",
-            exe_path_str
+            command.desktop_exec()
         );
-        
+
         fs::write(&self.desktop_file_path, desktop_content)
             .map_err(|e| format!("Failed to create autostart file: {}", e))?;
-        
+
         Ok(())
     }
-    
-    pub fn disable(&self) -> Result<(), String> {
+
+    fn disable_desktop_file(&self) -> Result<(), String> {
         if self.desktop_file_path.exists() {
             fs::remove_file(&self.desktop_file_path)
                 .map_err(|e| format!("Failed to remove autostart file: {}", e))?;
         }
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn enable_systemd(&self, command: &LaunchCommand) -> Result<(), String> {
+        if !systemd_available() {
+            return Err("No systemd user manager is running".to_string());
+        }
+
+        if let Some(dir) = self.unit_path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+
+        fs::write(&self.unit_path, unit_content(command))
+            .map_err(|e| format!("Failed to write {}: {}", self.unit_path.display(), e))?;
+
+        systemctl(&["daemon-reload"])?;
+        systemctl(&["enable", SERVICE_NAME])
+    }
+
+    fn disable_systemd(&self) -> Result<(), String> {
+        if !self.unit_path.exists() {
+            return Ok(());
+        }
+
+        if systemd_available() {
+            systemctl(&["disable", SERVICE_NAME])?;
+        }
+
+        fs::remove_file(&self.unit_path)
+            .map_err(|e| format!("Failed to remove {}: {}", self.unit_path.display(), e))?;
+
+        if systemd_available() {
+            systemctl(&["daemon-reload"]).ok();
+        }
+        Ok(())
+    }
+
+    /// `systemctl enable` links the unit into the target's wants directory,
+    /// checking for that link avoids spawning systemctl on every frame
+    fn systemd_enabled(&self) -> bool {
+        self.unit_path.exists()
+            && self.unit_path
+                .with_file_name("graphical-session.target.wants")
+                .join(SERVICE_NAME)
+                .exists()
+    }
+}
+
+/// Whether a systemd user manager is reachable for this session
+pub fn systemd_available() -> bool {
+    Command::new("systemctl")
+        .args(["--user", "show-environment"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// State of the service as reported by `systemctl --user is-active`
+/// (`active`, `inactive`, `failed`, ...)
+pub fn systemd_status() -> Option<String> {
+    let output = Command::new("systemctl")
+        .args(["--user", "is-active", SERVICE_NAME])
+        .output()
+        .ok()?;
+
+    let state = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!state.is_empty()).then_some(state)
+}
+
+/// Starts the daemon through its unit, so systemd supervises it
+pub fn start_service() -> Result<(), String> {
+    systemctl(&["start", SERVICE_NAME])
+}
+
+/// Stops the unit's daemon, without `Restart=` bringing it back
+pub fn stop_service() -> Result<(), String> {
+    systemctl(&["stop", SERVICE_NAME])
+}
+
+fn systemctl(args: &[&str]) -> Result<(), String> {
+    let output = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run systemctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "systemctl --user {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn unit_content(command: &LaunchCommand) -> String {
+    let environment: String = command.env
+        .iter()
+        .map(|(key, value)| format!("Environment={}\n", quote_systemd(&format!("{}={}", key, value))))
+        .collect();
+
+    format!(
+        "[Unit]
+Description=yoinkctl color picker daemon
+PartOf=graphical-session.target
+After=graphical-session.target
+
+[Service]
+Type=simple
+{}ExecStart={}
+Restart=on-failure
+RestartSec=2
+
+[Install]
+WantedBy=graphical-session.target
+",
+        environment,
+        command.systemd_exec()
+    )
+}
+
+/// What the autostart entry runs: `yoinkctl daemon [args...]` with extra environment
+struct LaunchCommand {
+    executable: String,
+    args: Vec<String>,
+    env: BTreeMap<String, String>,
+}
+
+impl LaunchCommand {
+    fn from_config(config: &Config) -> Result<Self, String> {
+        let mut args = vec!["daemon".to_string()];
+        args.extend(config.daemon_args.iter().cloned());
+
+        let executable = daemon_executable()?
+            .to_str()
+            .ok_or_else(|| "Invalid executable path".to_string())?
+            .to_string();
+
+        Ok(Self {
+            executable,
+            args,
+            env: config.daemon_env.clone(),
+        })
+    }
+
+    fn words(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.executable).chain(self.args.iter())
+    }
+
+    /// `Exec=` value following the desktop entry spec, with `env` in front
+    /// when extra variables are set
+    fn desktop_exec(&self) -> String {
+        let mut words: Vec<String> = Vec::new();
+        if !self.env.is_empty() {
+            words.push("env".to_string());
+            words.extend(self.env.iter().map(|(key, value)| format!("{}={}", key, value)));
+        }
+        words.extend(self.words().cloned());
+
+        let exec = words
+            .iter()
+            .map(|word| quote_desktop(word))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        // The string-level escape rule applies on top of the quoting rule
+        exec.replace('\\', "\\\\")
+    }
+
+    fn systemd_exec(&self) -> String {
+        self.words()
+            .map(|word| quote_systemd(word).replace('$', "$$"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// The path the session should launch. AppImages run from a temporary mount
+/// that's gone after a reboot, so point at the image itself.
+pub fn daemon_executable() -> Result<PathBuf, String> {
+    if let Some(appimage) = env::var_os("APPIMAGE").map(PathBuf::from) {
+        if appimage.exists() {
+            return Ok(appimage);
+        }
+    }
+
+    env::current_exe()
+        .map_err(|e| format!("Failed to get executable path: {}", e))
+}
+
+/// Whether an entry launching `executable` needs rewriting, `appdir` is the
+/// running AppImage's mount (`$APPDIR`)
+fn is_stale(executable: &Path, appdir: Option<&Path>) -> bool {
+    if !executable.exists() {
+        return true;
+    }
+
+    // Written from inside a running AppImage by an older version
+    appdir.is_some_and(|mount| executable.starts_with(mount))
+}
+
+fn quote_desktop(word: &str) -> String {
+    const RESERVED: &[char] = &[
+        ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(', ')', '`',
+    ];
+
+    let word = word.replace('%', "%%");
+    if !word.is_empty() && !word.contains(RESERVED) {
+        return word;
+    }
+
+    let mut quoted = String::from("\"");
+    for c in word.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn quote_systemd(word: &str) -> String {
+    let escaped = word
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%");
+    format!("\"{}\"", escaped)
+}
+
+/// Reads the executable back out of an entry's `Exec=`/`ExecStart=` line
+fn recorded_executable(content: &str, backend: AutostartBackend) -> Option<PathBuf> {
+    // Undo the file-level escapes, what's left is quoted the way `split_args` reads
+    let (content, key) = match backend {
+        AutostartBackend::DesktopFile => (content.replace("\\\\", "\\"), "Exec="),
+        AutostartBackend::Systemd => (content.replace("$$", "$"), "ExecStart="),
+    };
+    let value = content.lines().find_map(|line| line.strip_prefix(key))?;
+    let mut words = split_args(value).ok()?.into_iter();
+
+    let mut executable = words.next()?;
+    if executable == "env" {
+        executable = words.find(|word| !word.contains('='))?;
+    }
+
+    Some(PathBuf::from(executable.replace("%%", "%")))
+}
+
+/// Splits `text` on whitespace, keeping double-quoted parts together
+pub fn split_args(text: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut in_quotes = false;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                in_word = true;
+            }
+            '\\' if in_quotes => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err("Unterminated quote".to_string());
+    }
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Inverse of [`split_args`], for showing arguments in a text field
+pub fn join_args(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+                format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parses one `KEY=VALUE` per line, skipping blank lines and `#` comments
+pub fn parse_env(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut env = BTreeMap::new();
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = line.split_once('=')
+            .ok_or_else(|| format!("Expected KEY=VALUE, got '{}'", line))?;
+
+        let valid_key = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key {
+            return Err(format!("Invalid variable name '{}'", key));
+        }
+
+        env.insert(key.to_string(), value.to_string());
+    }
+
+    Ok(env)
+}
+
+pub fn format_env(env: &BTreeMap<String, String>) -> String {
+    env.iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AWKWARD_PATHS: &[&str] = &[
+        "/opt/my apps/yoinkctl",
+        "/home/me/100%/yoinkctl",
+        "/home/me/$HOME/yoinkctl",
+        "/home/me/say \"hi\"/yoinkctl",
+        "/home/me/back\\slash/yoinkctl",
+        "/home/me/%% $$ \\\" '`;/yoinkctl",
+    ];
+
+    fn launch(executable: &str, env: &[(&str, &str)]) -> LaunchCommand {
+        LaunchCommand {
+            executable: executable.to_string(),
+            args: vec!["daemon".to_string(), "--flag value".to_string()],
+            env: env.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+        }
+    }
+
+    fn desktop_entry(command: &LaunchCommand) -> String {
+        format!("[Desktop Entry]\nType=Application\nExec={}\n", command.desktop_exec())
+    }
+
+    #[test]
+    fn desktop_entries_round_trip_awkward_paths() {
+        for path in AWKWARD_PATHS {
+            for env in [&[][..], &[("WINIT_UNIX_BACKEND", "x11"), ("SPACED", "a b")][..]] {
+                let content = desktop_entry(&launch(path, env));
+                assert_eq!(
+                    recorded_executable(&content, AutostartBackend::DesktopFile),
+                    Some(PathBuf::from(path)),
+                    "{}",
+                    content
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn systemd_units_round_trip_awkward_paths() {
+        for path in AWKWARD_PATHS {
+            let content = unit_content(&launch(path, &[("WINIT_UNIX_BACKEND", "x11")]));
+            assert_eq!(
+                recorded_executable(&content, AutostartBackend::Systemd),
+                Some(PathBuf::from(path)),
+                "{}",
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn desktop_exec_quotes_only_when_needed() {
+        let command = launch("/usr/bin/yoinkctl", &[]);
+        assert_eq!(command.desktop_exec(), "/usr/bin/yoinkctl daemon \"--flag value\"");
+
+        let command = launch("/opt/50%/yoinkctl", &[("K", "a b")]);
+        assert_eq!(command.desktop_exec(), "env \"K=a b\" /opt/50%%/yoinkctl daemon \"--flag value\"");
+
+        // Quoting escapes `$`, then the string-level rule doubles the backslash
+        let command = launch("/opt/$x/yoinkctl", &[]);
+        assert!(command.desktop_exec().starts_with("\"/opt/\\\\$x/yoinkctl\" "));
+    }
+
+    #[test]
+    fn systemd_exec_escapes_specifiers_and_variables() {
+        let command = launch("/opt/50% $x/yoinkctl", &[]);
+        assert_eq!(command.systemd_exec(), "\"/opt/50%% $$x/yoinkctl\" \"daemon\" \"--flag value\"");
+        assert!(unit_content(&launch("/y", &[("K", "a\"b")])).contains("Environment=\"K=a\\\"b\"\n"));
+    }
+
+    #[test]
+    fn env_prefix_is_skipped_when_reading_entries() {
+        let content = "Exec=env A=1 \"B=two words\" /usr/bin/yoinkctl daemon\n";
+        assert_eq!(
+            recorded_executable(content, AutostartBackend::DesktopFile),
+            Some(PathBuf::from("/usr/bin/yoinkctl"))
+        );
+        assert_eq!(recorded_executable("Exec=env A=1\n", AutostartBackend::DesktopFile), None);
+        assert_eq!(recorded_executable("[Service]\n", AutostartBackend::Systemd), None);
+    }
+
+    #[test]
+    fn split_args_and_join_args_are_inverses() {
+        assert_eq!(split_args("  a  \"b c\" d\"e f\"g ").unwrap(), ["a", "b c", "de fg"]);
+        assert_eq!(split_args("\"\" \"a\\\"b\\\\c\"").unwrap(), ["", "a\"b\\c"]);
+        assert!(split_args("\"open").is_err());
+
+        let args: Vec<String> = ["plain", "two words", "", "quo\"te", "back\\slash", "tab\there"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(split_args(&join_args(&args)).unwrap(), args);
+    }
+
+    #[test]
+    fn entries_inside_the_appimage_mount_are_stale() {
+        let root = env::temp_dir().join(format!("yoinkctl-test-{}-appimage", std::process::id()));
+        let mount = root.join("mount");
+        fs::create_dir_all(mount.join("usr/bin")).unwrap();
+        let mounted = mount.join("usr/bin/yoinkctl");
+        let appimage = root.join("yoinkctl.AppImage");
+        fs::write(&mounted, "").unwrap();
+        fs::write(&appimage, "").unwrap();
+
+        assert!(is_stale(&mounted, Some(&mount)));
+        assert!(!is_stale(&appimage, Some(&mount)));
+        assert!(!is_stale(&mounted, None));
+        assert!(is_stale(&root.join("missing"), None));
+
+        fs::remove_dir_all(&root).ok();
+    }
+}
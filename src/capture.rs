@@ -0,0 +1,610 @@
+use image::{DynamicImage, RgbaImage};
+use std::path::PathBuf;
+use xcap::Monitor;
+
+use crate::color;
+use crate::portal;
+
+/// A screenshot and where its top-left pixel sits on the desktop
+pub struct Capture {
+    pub image: RgbaImage,
+    /// Desktop position of the top-left pixel, in the platform's desktop
+    /// units: physical pixels on X11 and Windows, points on macOS
+    pub offset: (i32, i32),
+    /// Image pixels per desktop unit, 2.0 for a Retina display on macOS
+    pub scale: f32,
+    /// Whether the alpha channel means something. It is always straight
+    /// (not premultiplied); screens are opaque and leave this unset.
+    pub alpha: bool,
+}
+
+/// Something that can produce the screenshot the picker samples from
+pub trait CaptureBackend {
+    /// Short name, as accepted by `YOINKCTL_CAPTURE`
+    fn name(&self) -> &'static str;
+
+    fn capture(&self) -> Result<Capture, String>;
+
+    /// Captures a desktop rectangle `(x, y, width, height)`, for the live
+    /// overlay. Backends that only take whole screenshots can't.
+    fn capture_region(&self, _region: (i32, i32, u32, u32)) -> Result<Capture, String> {
+        Err(format!("{} can't capture regions", self.name()))
+    }
+}
+
+/// Captures every monitor through `xcap` and stitches them into one desktop
+/// image, the overlay can open on any of them
+pub struct XcapBackend;
+
+impl CaptureBackend for XcapBackend {
+    fn name(&self) -> &'static str {
+        "xcap"
+    }
+
+    fn capture(&self) -> Result<Capture, String> {
+        let monitors = Monitor::all().map_err(|e| format!("Failed to list monitors: {}", e))?;
+        if monitors.is_empty() {
+            return Err("No monitors found".to_string());
+        }
+
+        let mut shots = Vec::with_capacity(monitors.len());
+        for monitor in &monitors {
+            let image = monitor.capture_image()
+                .map_err(|e| format!("Failed to capture monitor {}: {}", monitor.name(), e))?;
+            shots.push(((monitor.x(), monitor.y(), monitor.width(), monitor.height()), image));
+        }
+
+        Ok(stitch(shots))
+    }
+
+    /// xcap has no partial capture, this grabs the whole monitor and crops it
+    fn capture_region(&self, (x, y, width, height): (i32, i32, u32, u32)) -> Result<Capture, String> {
+        let monitor = Monitor::from_point(x, y).map_err(|e| format!("No monitor at {},{}: {}", x, y, e))?;
+        let image = monitor.capture_image()
+            .map_err(|e| format!("Failed to capture monitor: {}", e))?;
+        let scale = image.width() as f32 / monitor.width().max(1) as f32;
+
+        let to_pixels = |value: i32| (value as f32 * scale).round().max(0.0) as u32;
+        let (left, top) = (to_pixels(x - monitor.x()), to_pixels(y - monitor.y()));
+        let right = to_pixels(x - monitor.x() + width as i32).min(image.width());
+        let bottom = to_pixels(y - monitor.y() + height as i32).min(image.height());
+        if right <= left || bottom <= top {
+            return Err("Region is outside the monitor".to_string());
+        }
+
+        Ok(Capture {
+            image: image::imageops::crop_imm(&image, left, top, right - left, bottom - top).to_image(),
+            offset: (monitor.x() + (left as f32 / scale) as i32, monitor.y() + (top as f32 / scale) as i32),
+            scale,
+            alpha: false,
+        })
+    }
+}
+
+/// Places monitor captures `((x, y, width, height), image)` on one canvas
+/// covering all of them, gaps between monitors stay black.
+///
+/// macOS reports monitor geometry in points but captures in pixels, so
+/// monitors with fewer pixels per point than the sharpest one are scaled up.
+fn stitch(shots: Vec<((i32, i32, u32, u32), RgbaImage)>) -> Capture {
+    let scale = shots
+        .iter()
+        .map(|((_, _, width, _), image)| image.width() as f32 / (*width).max(1) as f32)
+        .fold(1.0, f32::max);
+
+    let left = shots.iter().map(|((x, ..), _)| *x).min().unwrap_or(0);
+    let top = shots.iter().map(|((_, y, ..), _)| *y).min().unwrap_or(0);
+    let right = shots.iter().map(|((x, _, width, _), _)| x + *width as i32).max().unwrap_or(0);
+    let bottom = shots.iter().map(|((_, y, _, height), _)| y + *height as i32).max().unwrap_or(0);
+
+    let to_pixels = |value: i32| (value as f32 * scale).round() as u32;
+    let mut canvas = RgbaImage::from_pixel(to_pixels(right - left), to_pixels(bottom - top), image::Rgba([0, 0, 0, 255]));
+
+    for ((x, y, width, height), image) in shots {
+        let (width, height) = (to_pixels(width as i32), to_pixels(height as i32));
+        let image = if image.dimensions() == (width, height) {
+            image
+        } else {
+            image::imageops::resize(&image, width, height, image::imageops::FilterType::Nearest)
+        };
+        image::imageops::replace(&mut canvas, &image, to_pixels(x - left) as i64, to_pixels(y - top) as i64);
+    }
+
+    Capture {
+        image: canvas,
+        offset: (left, top),
+        scale,
+        alpha: false,
+    }
+}
+
+/// Reads the X root window directly, through shared memory when the server
+/// supports it and a plain `XGetImage` otherwise
+pub struct X11Backend;
+
+impl CaptureBackend for X11Backend {
+    fn name(&self) -> &'static str {
+        "x11"
+    }
+
+    fn capture(&self) -> Result<Capture, String> {
+        x11::capture_root().map(|(image, alpha)| Capture { image, offset: (0, 0), scale: 1.0, alpha })
+    }
+
+    fn capture_region(&self, region: (i32, i32, u32, u32)) -> Result<Capture, String> {
+        x11::capture_area(region).map(|(image, offset, alpha)| Capture { image, offset, scale: 1.0, alpha })
+    }
+}
+
+/// Asks xdg-desktop-portal for a screenshot, for Wayland sessions
+pub struct PortalBackend;
+
+impl CaptureBackend for PortalBackend {
+    fn name(&self) -> &'static str {
+        "portal"
+    }
+
+    fn capture(&self) -> Result<Capture, String> {
+        // The portal returns the whole desktop in one image, any alpha channel
+        // in its PNG is just padding
+        portal::screenshot().map(|image| Capture { image, offset: (0, 0), scale: 1.0, alpha: false })
+    }
+}
+
+/// Replays an image file instead of the screen, e.g. a screenshot attached to a bug report
+pub struct FileBackend {
+    pub path: PathBuf,
+}
+
+impl CaptureBackend for FileBackend {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn capture(&self) -> Result<Capture, String> {
+        let image = image::open(&self.path)
+            .map_err(|e| format!("Failed to open {}: {}", self.path.display(), e))?;
+        let alpha = image.color().has_alpha();
+
+        let image = match image {
+            // Radiance HDR and OpenEXR hold linear light, encode it as sRGB.
+            // Anything brighter than SDR white is clipped, there is no tone mapping.
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => {
+                let linear = image.to_rgba32f();
+                RgbaImage::from_fn(linear.width(), linear.height(), |x, y| {
+                    let [r, g, b, a] = linear.get_pixel(x, y).0;
+                    image::Rgba([
+                        color::linear_to_srgb(r),
+                        color::linear_to_srgb(g),
+                        color::linear_to_srgb(b),
+                        (a.clamp(0.0, 1.0) * 255.0).round() as u8,
+                    ])
+                })
+            }
+            // 16-bit PNGs and TIFFs are scaled down to 8 bits per channel
+            image => image.to_rgba8(),
+        };
+
+        Ok(Capture {
+            image,
+            offset: (0, 0),
+            scale: 1.0,
+            alpha,
+        })
+    }
+}
+
+/// Backends to try in order.
+///
+/// `YOINKCTL_CAPTURE` picks one explicitly (`xcap`, `x11`, `portal`, or a path
+/// to an image file), otherwise the choice follows the session type.
+pub fn backends() -> Vec<Box<dyn CaptureBackend>> {
+    match std::env::var("YOINKCTL_CAPTURE").as_deref() {
+        Ok("xcap") => vec![Box::new(XcapBackend)],
+        Ok("x11") => vec![Box::new(X11Backend)],
+        Ok("portal") => vec![Box::new(PortalBackend)],
+        Ok(path) if !path.is_empty() && path != "auto" => vec![Box::new(FileBackend {
+            path: PathBuf::from(path.strip_prefix("file:").unwrap_or(path)),
+        })],
+        _ if portal::should_use() => vec![Box::new(PortalBackend), Box::new(XcapBackend)],
+        _ => vec![Box::new(X11Backend), Box::new(XcapBackend)],
+    }
+}
+
+/// Captures the screen with the first backend that works
+pub fn capture_screen() -> Option<Capture> {
+    for backend in backends() {
+        match backend.capture() {
+            Ok(capture) => return Some(capture),
+            Err(e) => eprintln!("⚠️  {} capture failed: {}", backend.name(), e),
+        }
+    }
+
+    None
+}
+
+/// Captures a desktop rectangle with the first backend that can, for the live
+/// overlay. Failures aren't logged here since this runs many times a second.
+pub fn capture_region(region: (i32, i32, u32, u32)) -> Result<Capture, String> {
+    let mut errors = Vec::new();
+    for backend in backends() {
+        match backend.capture_region(region) {
+            Ok(capture) => return Ok(capture),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    Err(errors.join("; "))
+}
+
+/// Undoes premultiplication, so a half transparent red reads as red at 50%
+/// rather than dark red
+pub fn unpremultiply([r, g, b, a]: [u8; 4]) -> [u8; 4] {
+    if a == 0 || a == 255 {
+        return [r, g, b, a];
+    }
+    let channel = |c: u8| ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8;
+    [channel(r), channel(g), channel(b), a]
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use image::RgbaImage;
+    use std::os::raw::{c_char, c_int, c_ulong};
+    use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+    use std::sync::{Mutex, OnceLock};
+    use x11_dl::{xlib, xshm};
+
+    static X_ERROR: AtomicBool = AtomicBool::new(false);
+    /// The connection a capture is running on, its errors land in `X_ERROR`
+    static CAPTURING: AtomicPtr<xlib::Display> = AtomicPtr::new(std::ptr::null_mut());
+    /// One capture at a time, they share `X_ERROR`
+    static CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+    /// The handler ours replaced, it keeps handling every other connection
+    static PREVIOUS_HANDLER: OnceLock<ErrorHandler> = OnceLock::new();
+
+    type ErrorHandler = Option<unsafe extern "C" fn(*mut xlib::Display, *mut xlib::XErrorEvent) -> c_int>;
+
+    unsafe extern "C" fn on_x_error(display: *mut xlib::Display, event: *mut xlib::XErrorEvent) -> c_int {
+        if display == CAPTURING.load(Ordering::SeqCst) {
+            X_ERROR.store(true, Ordering::SeqCst);
+            return 0;
+        }
+
+        // Someone else's connection, e.g. winit's
+        match PREVIOUS_HANDLER.get().copied().flatten() {
+            Some(handler) => handler(display, event),
+            None => 0,
+        }
+    }
+
+    /// The root window as RGBA, and whether it has an alpha channel
+    pub fn capture_root() -> Result<(RgbaImage, bool), String> {
+        with_display(|xlib, display| unsafe { capture_display(xlib, display) })
+    }
+
+    /// Part of the root window, clipped to the screen, with the desktop
+    /// position it ended up starting at
+    pub fn capture_area((x, y, width, height): (i32, i32, u32, u32)) -> Result<(RgbaImage, (i32, i32), bool), String> {
+        with_display(|xlib, display| unsafe {
+            let root = (xlib.XDefaultRootWindow)(display);
+            let mut attributes: xlib::XWindowAttributes = std::mem::zeroed();
+            if (xlib.XGetWindowAttributes)(display, root, &mut attributes) == 0 {
+                return Err("Failed to query the root window".to_string());
+            }
+
+            let (left, top) = (x.max(0), y.max(0));
+            let right = (x + width as i32).min(attributes.width);
+            let bottom = (y + height as i32).min(attributes.height);
+            if right <= left || bottom <= top {
+                return Err("Region is outside the screen".to_string());
+            }
+
+            X_ERROR.store(false, Ordering::SeqCst);
+            let image = (xlib.XGetImage)(display, root, left, top, (right - left) as u32, (bottom - top) as u32, !0, xlib::ZPixmap);
+            if image.is_null() || X_ERROR.load(Ordering::SeqCst) {
+                return Err("XGetImage failed".to_string());
+            }
+
+            let result = to_rgba(&*image);
+            (xlib.XDestroyImage)(image);
+            result.map(|(image, alpha)| (image, (left, top), alpha))
+        })
+    }
+
+    fn with_display<T>(f: impl FnOnce(&xlib::Xlib, *mut xlib::Display) -> Result<T, String>) -> Result<T, String> {
+        let xlib = xlib::Xlib::open().map_err(|e| format!("Failed to load Xlib: {}", e))?;
+
+        unsafe {
+            let display = (xlib.XOpenDisplay)(std::ptr::null());
+            if display.is_null() {
+                return Err("Cannot open X display".to_string());
+            }
+
+            // Without a handler, a failed request (e.g. XShm on a remote display)
+            // exits the process. The handler is process-wide, so it's installed
+            // once instead of swapped under other threads' connections.
+            PREVIOUS_HANDLER.get_or_init(|| (xlib.XSetErrorHandler)(Some(on_x_error)));
+
+            let _capturing = CAPTURE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            CAPTURING.store(display, Ordering::SeqCst);
+            X_ERROR.store(false, Ordering::SeqCst);
+
+            let result = f(&xlib, display);
+
+            (xlib.XCloseDisplay)(display);
+            CAPTURING.store(std::ptr::null_mut(), Ordering::SeqCst);
+            result
+        }
+    }
+
+    unsafe fn capture_display(xlib: &xlib::Xlib, display: *mut xlib::Display) -> Result<(RgbaImage, bool), String> {
+        let root = (xlib.XDefaultRootWindow)(display);
+
+        let mut attributes: xlib::XWindowAttributes = std::mem::zeroed();
+        if (xlib.XGetWindowAttributes)(display, root, &mut attributes) == 0 {
+            return Err("Failed to query the root window".to_string());
+        }
+        let (width, height) = (attributes.width as u32, attributes.height as u32);
+
+        match capture_shm(xlib, display, root, &attributes) {
+            Ok(image) => return Ok(image),
+            Err(e) => eprintln!("⚠️  XShm capture unavailable ({}), using XGetImage", e),
+        }
+
+        X_ERROR.store(false, Ordering::SeqCst);
+        let image = (xlib.XGetImage)(display, root, 0, 0, width, height, !0, xlib::ZPixmap);
+        if image.is_null() || X_ERROR.load(Ordering::SeqCst) {
+            return Err("XGetImage failed".to_string());
+        }
+
+        let result = to_rgba(&*image);
+        (xlib.XDestroyImage)(image);
+        result
+    }
+
+    unsafe fn capture_shm(
+        xlib: &xlib::Xlib,
+        display: *mut xlib::Display,
+        root: xlib::Window,
+        attributes: &xlib::XWindowAttributes,
+    ) -> Result<(RgbaImage, bool), String> {
+        let xext = xshm::Xext::open().map_err(|e| format!("Failed to load Xext: {}", e))?;
+
+        if (xext.XShmQueryExtension)(display) == 0 {
+            return Err("MIT-SHM extension missing".to_string());
+        }
+
+        let (width, height) = (attributes.width as u32, attributes.height as u32);
+        let mut info: xshm::XShmSegmentInfo = std::mem::zeroed();
+
+        let image = (xext.XShmCreateImage)(
+            display,
+            attributes.visual,
+            attributes.depth as u32,
+            xlib::ZPixmap,
+            std::ptr::null_mut(),
+            &mut info,
+            width,
+            height,
+        );
+        if image.is_null() {
+            return Err("XShmCreateImage failed".to_string());
+        }
+
+        let size = (*image).bytes_per_line as usize * height as usize;
+        info.shmid = libc::shmget(libc::IPC_PRIVATE, size, libc::IPC_CREAT | 0o600);
+        if info.shmid < 0 {
+            (xlib.XDestroyImage)(image);
+            return Err(format!("shmget failed: {}", std::io::Error::last_os_error()));
+        }
+
+        let address = libc::shmat(info.shmid, std::ptr::null(), 0);
+        if address as isize == -1 {
+            libc::shmctl(info.shmid, libc::IPC_RMID, std::ptr::null_mut());
+            (xlib.XDestroyImage)(image);
+            return Err(format!("shmat failed: {}", std::io::Error::last_os_error()));
+        }
+        info.shmaddr = address as *mut c_char;
+        (*image).data = info.shmaddr;
+
+        X_ERROR.store(false, Ordering::SeqCst);
+        (xext.XShmAttach)(display, &mut info);
+        (xlib.XSync)(display, xlib::False);
+
+        // Once both sides are attached, mark the segment so the kernel frees it
+        // even if we crash before detaching
+        libc::shmctl(info.shmid, libc::IPC_RMID, std::ptr::null_mut());
+
+        let result = if X_ERROR.load(Ordering::SeqCst) {
+            Err("XShmAttach failed (remote display?)".to_string())
+        } else if (xext.XShmGetImage)(display, root, image, 0, 0, !0) == 0 {
+            Err("XShmGetImage failed".to_string())
+        } else {
+            to_rgba(&*image)
+        };
+
+        (xext.XShmDetach)(display, &mut info);
+        (xlib.XSync)(display, xlib::False);
+        (xlib.XDestroyImage)(image);
+        libc::shmdt(address);
+
+        result
+    }
+
+    /// Converts a 32-bit ZPixmap using its channel masks, so both BGRX and RGBX layouts work.
+    ///
+    /// Depth 32 visuals carry premultiplied alpha in the spare byte, depth 24
+    /// ones leave it undefined and come out opaque.
+    unsafe fn to_rgba(image: &xlib::XImage) -> Result<(RgbaImage, bool), String> {
+        if image.bits_per_pixel != 32 {
+            return Err(format!("Unsupported X image format ({} bits per pixel)", image.bits_per_pixel));
+        }
+
+        let (width, height) = (image.width as u32, image.height as u32);
+        let stride = image.bytes_per_line as usize;
+        let data = std::slice::from_raw_parts(image.data as *const u8, stride * height as usize);
+
+        let channel = |pixel: u32, mask: c_ulong| -> u8 {
+            let mask = mask as u32;
+            if mask == 0 {
+                return 0;
+            }
+            let value = (pixel & mask) >> mask.trailing_zeros();
+            let max = mask >> mask.trailing_zeros();
+            (value * 255 / max) as u8
+        };
+
+        let has_alpha = image.depth == 32;
+        let alpha_mask = !(image.red_mask | image.green_mask | image.blue_mask) & 0xFFFF_FFFF;
+
+        let mut rgba = RgbaImage::new(width, height);
+        for y in 0..height {
+            let row = &data[y as usize * stride..];
+            for x in 0..width {
+                let bytes = [row[x as usize * 4], row[x as usize * 4 + 1], row[x as usize * 4 + 2], row[x as usize * 4 + 3]];
+                let pixel = if image.byte_order == xlib::LSBFirst {
+                    u32::from_le_bytes(bytes)
+                } else {
+                    u32::from_be_bytes(bytes)
+                };
+
+                let rgb = [
+                    channel(pixel, image.red_mask),
+                    channel(pixel, image.green_mask),
+                    channel(pixel, image.blue_mask),
+                ];
+                let pixel = if has_alpha {
+                    super::unpremultiply([rgb[0], rgb[1], rgb[2], channel(pixel, alpha_mask)])
+                } else {
+                    [rgb[0], rgb[1], rgb[2], 255]
+                };
+                rgba.put_pixel(x, y, image::Rgba(pixel));
+            }
+        }
+
+        Ok((rgba, has_alpha))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod x11 {
+    use image::RgbaImage;
+
+    pub fn capture_root() -> Result<(RgbaImage, bool), String> {
+        Err("Direct X11 capture is only available on Linux".to_string())
+    }
+
+    pub fn capture_area(_region: (i32, i32, u32, u32)) -> Result<(RgbaImage, (i32, i32), bool), String> {
+        Err("Direct X11 capture is only available on Linux".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb, Rgba};
+
+    fn temp_png(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("yoinkctl-test-{}-{}.png", std::process::id(), name))
+    }
+
+    fn load(path: &PathBuf) -> Capture {
+        let capture = FileBackend { path: path.clone() }.capture();
+        std::fs::remove_file(path).ok();
+        capture.unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    #[test]
+    fn file_backend_keeps_straight_alpha() {
+        let path = temp_png("rgba");
+        let image = RgbaImage::from_fn(4, 3, |x, y| match (x, y) {
+            (0, 0) => Rgba([255, 0, 0, 255]),
+            // Half transparent red, stored straight as PNG requires
+            (1, 0) => Rgba([255, 0, 0, 128]),
+            (3, 2) => Rgba([12, 34, 56, 0]),
+            _ => Rgba([10 * x as u8, 20 * y as u8, 30, 255]),
+        });
+        image.save(&path).unwrap();
+
+        let capture = load(&path);
+        assert!(capture.alpha);
+        assert_eq!(capture.offset, (0, 0));
+        assert_eq!(capture.scale, 1.0);
+        assert_eq!(capture.image.dimensions(), (4, 3));
+        assert_eq!(capture.image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(capture.image.get_pixel(1, 0).0, [255, 0, 0, 128]);
+        assert_eq!(capture.image.get_pixel(2, 1).0, [20, 20, 30, 255]);
+        assert_eq!(capture.image.get_pixel(3, 2).0, [12, 34, 56, 0]);
+    }
+
+    #[test]
+    fn file_backend_opaque_and_16_bit_images() {
+        let path = temp_png("rgb");
+        RgbaImage::from_pixel(2, 2, Rgba([1, 2, 3, 255])).save(&path).unwrap();
+        let rgb_path = temp_png("rgb8");
+        image::RgbImage::from_pixel(2, 2, Rgb([200, 100, 50])).save(&rgb_path).unwrap();
+        let wide_path = temp_png("rgb16");
+        ImageBuffer::<Rgb<u16>, _>::from_pixel(1, 1, Rgb([65535, 32896, 0])).save(&wide_path).unwrap();
+
+        // An RGBA file keeps its alpha flag even when every pixel is opaque
+        assert!(load(&path).alpha);
+
+        let capture = load(&rgb_path);
+        assert!(!capture.alpha);
+        assert_eq!(capture.image.get_pixel(1, 1).0, [200, 100, 50, 255]);
+
+        let capture = load(&wide_path);
+        assert!(!capture.alpha);
+        assert_eq!(capture.image.get_pixel(0, 0).0, [255, 128, 0, 255]);
+    }
+
+    #[test]
+    fn file_backend_reports_missing_files() {
+        let path = temp_png("missing");
+        assert!(FileBackend { path }.capture().is_err());
+    }
+
+    #[test]
+    fn unpremultiply_restores_straight_color() {
+        assert_eq!(unpremultiply([128, 0, 0, 128]), [255, 0, 0, 128]);
+        assert_eq!(unpremultiply([32, 64, 16, 64]), [128, 255, 64, 64]);
+        // Opaque and fully transparent pixels are left alone
+        assert_eq!(unpremultiply([10, 20, 30, 255]), [10, 20, 30, 255]);
+        assert_eq!(unpremultiply([10, 20, 30, 0]), [10, 20, 30, 0]);
+        // Channels above alpha aren't valid premultiplied values, they clamp
+        assert_eq!(unpremultiply([200, 0, 0, 100]), [255, 0, 0, 100]);
+    }
+
+    #[test]
+    fn stitch_places_monitors_on_the_desktop() {
+        let red = RgbaImage::from_pixel(4, 2, Rgba([255, 0, 0, 255]));
+        // Right of the first one and a bit lower, leaving a gap above it
+        let blue = RgbaImage::from_pixel(2, 3, Rgba([0, 0, 255, 255]));
+        let capture = stitch(vec![((-4, 0, 4, 2), red), ((0, 1, 2, 3), blue)]);
+
+        assert_eq!(capture.offset, (-4, 0));
+        assert_eq!(capture.scale, 1.0);
+        assert!(!capture.alpha);
+        assert_eq!(capture.image.dimensions(), (6, 4));
+        assert_eq!(capture.image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(capture.image.get_pixel(4, 1).0, [0, 0, 255, 255]);
+        assert_eq!(capture.image.get_pixel(5, 3).0, [0, 0, 255, 255]);
+        assert_eq!(capture.image.get_pixel(4, 0).0, [0, 0, 0, 255]);
+        assert_eq!(capture.image.get_pixel(0, 3).0, [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn stitch_scales_up_to_the_sharpest_monitor() {
+        // A Retina monitor captures 2x2 pixels per point, its neighbour 1
+        let retina = RgbaImage::from_pixel(4, 4, Rgba([255, 0, 0, 255]));
+        let plain = RgbaImage::from_pixel(2, 2, Rgba([0, 255, 0, 255]));
+        let capture = stitch(vec![((0, 0, 2, 2), retina), ((2, 0, 2, 2), plain)]);
+
+        assert_eq!(capture.scale, 2.0);
+        assert_eq!(capture.image.dimensions(), (8, 4));
+        assert_eq!(capture.image.get_pixel(3, 3).0, [255, 0, 0, 255]);
+        assert_eq!(capture.image.get_pixel(4, 0).0, [0, 255, 0, 255]);
+        assert_eq!(capture.image.get_pixel(7, 3).0, [0, 255, 0, 255]);
+    }
+}
@@ -0,0 +1,256 @@
+use image::RgbaImage;
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::borrow::Cow;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::process::{Command, Stdio};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use arboard::{Clipboard, ImageData, LinuxClipboardKind, SetExtLinux};
+    use image::RgbaImage;
+
+    use crate::ipc::{self, Request};
+
+    static IN_PROCESS: AtomicBool = AtomicBool::new(false);
+
+    /// arboard stops serving the selections once its last `Clipboard` is dropped
+    static HELD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+    enum Content {
+        Text(String),
+        Image(RgbaImage),
+    }
+
+    fn set(clipboard: &mut Clipboard, content: &Content, selection: LinuxClipboardKind, wait: bool) -> Result<(), arboard::Error> {
+        let mut set = clipboard.set().clipboard(selection);
+        if wait {
+            set = set.wait();
+        }
+
+        match content {
+            Content::Text(text) => set.text(text.as_str()),
+            Content::Image(image) => set.image(ImageData {
+                width: image.width() as usize,
+                height: image.height() as usize,
+                bytes: Cow::Borrowed(image.as_raw()),
+            }),
+        }
+    }
+
+    pub fn copy_text(text: &str, primary: bool) -> Result<(), String> {
+        if IN_PROCESS.load(Ordering::Relaxed) {
+            return copy_in_process(text, primary);
+        }
+
+        // The control socket is line based, anything else goes to the helper
+        if !text.contains('\n') {
+            let request = Request::Copy { text: text.to_string(), primary };
+            if ipc::send(request).is_ok() {
+                return Ok(());
+            }
+        }
+
+        spawn_helper(&Content::Text(text.to_string()), primary)
+    }
+
+    pub fn copy_image(image: RgbaImage) -> Result<(), String> {
+        let content = Content::Image(image);
+        if IN_PROCESS.load(Ordering::Relaxed) {
+            copy_content_in_process(&content, false)
+        } else {
+            spawn_helper(&content, false)
+        }
+    }
+
+    pub fn copy_in_process(text: &str, primary: bool) -> Result<(), String> {
+        copy_content_in_process(&Content::Text(text.to_string()), primary)
+    }
+
+    fn copy_content_in_process(content: &Content, primary: bool) -> Result<(), String> {
+        let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+        let clipboard = match held.as_mut() {
+            Some(clipboard) => clipboard,
+            None => held.insert(Clipboard::new().map_err(|e| format!("Clipboard unavailable: {}", e))?),
+        };
+
+        set(clipboard, content, LinuxClipboardKind::Clipboard, false)
+            .map_err(|e| format!("Failed to copy: {}", e))?;
+
+        if primary {
+            set(clipboard, content, LinuxClipboardKind::Primary, false)
+                .map_err(|e| format!("Failed to set the primary selection: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn keep_in_process() {
+        IN_PROCESS.store(true, Ordering::Relaxed);
+    }
+
+    fn spawn_helper(content: &Content, primary: bool) -> Result<(), String> {
+        use std::os::unix::process::CommandExt;
+
+        let exe_path = std::env::current_exe()
+            .map_err(|e| format!("Failed to get exe path: {}", e))?;
+
+        let mut command = Command::new(exe_path);
+        command.arg("clipboard-serve");
+        if primary {
+            command.arg("--primary");
+        }
+        let bytes = match content {
+            Content::Text(text) => text.as_bytes(),
+            Content::Image(image) => {
+                command.arg("--image").arg(format!("{}x{}", image.width(), image.height()));
+                image.as_raw().as_slice()
+            }
+        };
+
+        // Its own process group, so closing the terminal a pick was started
+        // from doesn't take the clipboard with it
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn()
+            .map_err(|e| format!("Failed to start clipboard helper: {}", e))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(bytes)
+                .map_err(|e| format!("Failed to pass data to clipboard helper: {}", e))?;
+        }
+
+        let mut answer = String::new();
+        if let Some(stdout) = child.stdout.take() {
+            BufReader::new(stdout).read_line(&mut answer).ok();
+        }
+
+        // Reaps the helper in long-lived processes like the config app
+        std::thread::spawn(move || child.wait());
+
+        match answer.trim() {
+            "ok" => Ok(()),
+            "" => Err("Clipboard helper exited without copying".to_string()),
+            error => Err(error.to_string()),
+        }
+    }
+
+    pub fn serve_until_replaced(primary: bool, image_size: Option<(u32, u32)>) -> Result<(), String> {
+        let mut bytes = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read data to copy: {}", e))?;
+
+        let content = match image_size {
+            Some((width, height)) => RgbaImage::from_raw(width, height, bytes)
+                .map(Content::Image)
+                .ok_or_else(|| format!("Image data doesn't match {}x{}", width, height)),
+            None => String::from_utf8(bytes)
+                .map(Content::Text)
+                .map_err(|e| format!("Text to copy isn't UTF-8: {}", e)),
+        };
+
+        let copied = content.and_then(|content| copy_content_in_process(&content, primary).map(|()| content));
+        match &copied {
+            Ok(_) => println!("ok"),
+            Err(e) => println!("{}", e),
+        }
+        std::io::stdout().flush().ok();
+        let content = Arc::new(copied?);
+
+        let mut selections = vec![LinuxClipboardKind::Clipboard];
+        if primary {
+            selections.push(LinuxClipboardKind::Primary);
+        }
+
+        let waiting: Vec<_> = selections
+            .into_iter()
+            .map(|selection| {
+                let content = content.clone();
+                std::thread::spawn(move || {
+                    Clipboard::new().and_then(|mut clipboard| set(&mut clipboard, &content, selection, true))
+                })
+            })
+            .collect();
+
+        for selection in waiting {
+            selection.join().ok();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use arboard::Clipboard;
+    use image::RgbaImage;
+
+    // Other platforms keep clipboard contents after the process exits
+    pub fn copy_text(text: &str, _primary: bool) -> Result<(), String> {
+        copy_in_process(text, false)
+    }
+
+    pub fn copy_image(image: RgbaImage) -> Result<(), String> {
+        let (width, height) = image.dimensions();
+        Clipboard::new()
+            .map_err(|e| format!("Clipboard unavailable: {}", e))?
+            .set_image(arboard::ImageData {
+                width: width as usize,
+                height: height as usize,
+                bytes: image.into_raw().into(),
+            })
+            .map_err(|e| format!("Failed to copy image: {}", e))
+    }
+
+    pub fn copy_in_process(text: &str, _primary: bool) -> Result<(), String> {
+        Clipboard::new()
+            .map_err(|e| format!("Clipboard unavailable: {}", e))?
+            .set_text(text)
+            .map_err(|e| format!("Failed to copy: {}", e))
+    }
+
+    pub fn keep_in_process() {}
+
+    pub fn serve_until_replaced(_primary: bool, _image_size: Option<(u32, u32)>) -> Result<(), String> {
+        Err("The clipboard helper is only needed on X11".to_string())
+    }
+}
+
+/// Copies `text` so it's still there after this process exits.
+///
+/// X11 selections are served by the process that set them, so on Linux the
+/// text goes to the running daemon, or else to a `yoinkctl clipboard-serve`
+/// helper that keeps serving it until something else is copied. `primary`
+/// also sets the PRIMARY selection, pasted with a middle click.
+pub fn copy_text(text: &str, primary: bool) -> Result<(), String> {
+    imp::copy_text(text, primary)
+}
+
+/// Copies an image the same way as [`copy_text`], always through the helper
+/// unless this process serves the clipboard itself
+pub fn copy_image(image: RgbaImage) -> Result<(), String> {
+    imp::copy_image(image)
+}
+
+/// Sets the selections from this process and keeps serving them for as long
+/// as it runs, for the daemon and the helper
+pub fn copy_in_process(text: &str, primary: bool) -> Result<(), String> {
+    imp::copy_in_process(text, primary)
+}
+
+/// Marks this process as long-lived, so [`copy_text`] serves the selections
+/// itself instead of handing them off
+pub fn keep_in_process() {
+    imp::keep_in_process()
+}
+
+/// Body of `yoinkctl clipboard-serve`: copies stdin, answers `ok` or the error
+/// on stdout, then serves it until another client takes the selections.
+/// Stdin is text, or raw RGBA pixels when `image_size` is given.
+pub fn serve_until_replaced(primary: bool, image_size: Option<(u32, u32)>) -> Result<(), String> {
+    imp::serve_until_replaced(primary, image_size)
+}
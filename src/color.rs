@@ -0,0 +1,268 @@
+/// A color in Björn Ottosson's OKLab space, where euclidean distance roughly
+/// matches perceived difference
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl Oklab {
+    pub fn from_rgb((r, g, b): (u8, u8, u8)) -> Self {
+        let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+        Self {
+            l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        }
+    }
+
+    /// Back to 8-bit sRGB, clipping anything outside the gamut
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        let l = self.l + 0.396_337_78 * self.a + 0.215_803_76 * self.b;
+        let m = self.l - 0.105_561_346 * self.a - 0.063_854_17 * self.b;
+        let s = self.l - 0.089_484_18 * self.a - 1.291_485_5 * self.b;
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+
+        (
+            linear_to_srgb(4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s),
+            linear_to_srgb(-1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s),
+            linear_to_srgb(-0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s),
+        )
+    }
+
+    fn in_gamut(self) -> bool {
+        let l = self.l + 0.396_337_78 * self.a + 0.215_803_76 * self.b;
+        let m = self.l - 0.105_561_346 * self.a - 0.063_854_17 * self.b;
+        let s = self.l - 0.089_484_18 * self.a - 1.291_485_5 * self.b;
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+
+        [
+            4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        ]
+        .iter()
+        .all(|c| (-0.0001..=1.0001).contains(c))
+    }
+
+    pub fn distance_squared(self, other: Self) -> f32 {
+        let (dl, da, db) = (self.l - other.l, self.a - other.a, self.b - other.b);
+        dl * dl + da * da + db * db
+    }
+}
+
+/// OKLab in polar form: lightness, chroma and hue in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+impl Oklch {
+    pub fn from_rgb(rgb: (u8, u8, u8)) -> Self {
+        let lab = Oklab::from_rgb(rgb);
+        Self {
+            l: lab.l,
+            c: lab.a.hypot(lab.b),
+            h: lab.b.atan2(lab.a).to_degrees().rem_euclid(360.0),
+        }
+    }
+
+    fn to_oklab(self) -> Oklab {
+        let h = self.h.to_radians();
+        Oklab { l: self.l, a: self.c * h.cos(), b: self.c * h.sin() }
+    }
+
+    /// Back to sRGB, lowering chroma until the color fits rather than
+    /// clipping channels, which would shift its hue
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        if self.to_oklab().in_gamut() {
+            return self.to_oklab().to_rgb();
+        }
+
+        let (mut low, mut high) = (0.0, self.c);
+        for _ in 0..16 {
+            let mid = (low + high) / 2.0;
+            let candidate = Oklch { c: mid, ..self };
+            if candidate.to_oklab().in_gamut() {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Oklch { c: low, ..self }.to_oklab().to_rgb()
+    }
+}
+
+pub fn srgb_to_linear(c: u8) -> f32 {
+    srgb_to_linear_f32(c as f32 / 255.0)
+}
+
+/// The sRGB transfer curve for a channel in 0..1
+pub fn srgb_to_linear_f32(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+/// `#RRGGBB`, or `#RRGGBBAA` when the source has alpha
+pub fn hex_string((r, g, b): (u8, u8, u8), alpha: Option<u8>) -> String {
+    match alpha {
+        Some(a) => format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a),
+        None => format!("#{:02X}{:02X}{:02X}", r, g, b),
+    }
+}
+
+/// CSS alpha value, e.g. `0.5` or `1`
+pub fn alpha_fraction(alpha: u8) -> String {
+    let text = format!("{:.2}", alpha as f32 / 255.0);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Hue in degrees, saturation and lightness in 0..1
+pub fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let r = r as f32 / 255.0;
+    let g = g as f32 / 255.0;
+    let b = b as f32 / 255.0;
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let l = (max + min) / 2.0;
+
+    let s = if delta == 0.0 {
+        0.0
+    } else {
+        delta / (1.0 - (2.0 * l - 1.0).abs())
+    };
+
+    let h = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * (((g - b) / delta) % 6.0)
+    } else if max == g {
+        60.0 * (((b - r) / delta) + 2.0)
+    } else {
+        60.0 * (((r - g) / delta) + 4.0)
+    };
+
+    let h = if h < 0.0 { h + 360.0 } else { h };
+
+    (h, s, l)
+}
+
+pub fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (u8, u8, u8) {
+    let h = h.rem_euclid(360.0);
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = l - c / 2.0;
+
+    let (r, g, b) = match h as u32 / 60 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let channel = |v: f32| ((v + m).clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    (channel(r), channel(g), channel(b))
+}
+
+/// Color vision deficiencies the picker can simulate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cvd {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+    Achromatopsia,
+}
+
+impl Cvd {
+    pub const ALL: [Cvd; 4] = [Cvd::Protanopia, Cvd::Deuteranopia, Cvd::Tritanopia, Cvd::Achromatopsia];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Cvd::Protanopia => "Protanopia",
+            Cvd::Deuteranopia => "Deuteranopia",
+            Cvd::Tritanopia => "Tritanopia",
+            Cvd::Achromatopsia => "Achromatopsia",
+        }
+    }
+
+    /// Off → each deficiency in turn → off
+    pub fn cycle(current: Option<Cvd>) -> Option<Cvd> {
+        match current {
+            None => Some(Cvd::ALL[0]),
+            Some(cvd) => Cvd::ALL.iter().skip_while(|&&c| c != cvd).nth(1).copied(),
+        }
+    }
+
+    /// Machado, Oliveira & Fernandes (2009) at full severity, in linear RGB.
+    /// Achromatopsia keeps only luminance.
+    fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            Cvd::Protanopia => [
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            Cvd::Deuteranopia => [
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_820, 0.042_940, 0.968_881],
+            ],
+            Cvd::Tritanopia => [
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_900],
+            ],
+            Cvd::Achromatopsia => [[0.2126, 0.7152, 0.0722]; 3],
+        }
+    }
+
+    pub fn simulate(self, (r, g, b): (u8, u8, u8)) -> (u8, u8, u8) {
+        let linear = [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b)];
+        let [r, g, b] = self.matrix().map(|row| row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2]);
+        (linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b))
+    }
+
+    /// Simulates a whole image, with lookup tables so a 4K screenshot takes
+    /// milliseconds rather than a second of `powf`
+    pub fn simulate_image(self, image: &image::RgbaImage) -> image::RgbaImage {
+        const STEPS: usize = 4096;
+        let to_linear: Vec<f32> = (0..=255).map(srgb_to_linear).collect();
+        let to_srgb: Vec<u8> = (0..STEPS).map(|i| linear_to_srgb(i as f32 / (STEPS - 1) as f32)).collect();
+        let encode = |c: f32| to_srgb[(c.clamp(0.0, 1.0) * (STEPS - 1) as f32).round() as usize];
+        let matrix = self.matrix();
+
+        let mut simulated = image.clone();
+        for pixel in simulated.pixels_mut() {
+            let linear = [to_linear[pixel[0] as usize], to_linear[pixel[1] as usize], to_linear[pixel[2] as usize]];
+            let [r, g, b] = matrix.map(|row| row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2]);
+            pixel.0 = [encode(r), encode(g), encode(b), pixel[3]];
+        }
+        simulated
+    }
+}
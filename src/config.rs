@@ -1,24 +1,89 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
-use global_hotkey::hotkey::{Modifiers, Code};
+use crate::hotkey::Hotkey;
+use crate::icc::ManagedSpace;
+use crate::theme::Theme;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
-    pub hotkey: String,
+    #[serde(deserialize_with = "lenient_hotkey")]
+    pub hotkey: Hotkey,
     pub show_hex: bool,
     pub show_rgb: bool,
     pub show_hsl: bool,
+    /// Grid lines between the magnifier's cells
+    pub show_grid: bool,
+    /// Desktop X/Y of the sampled pixel in the info box
+    pub show_coordinates: bool,
     pub preview_size: u32,
+    /// Keep a hidden picker window inside the daemon instead of starting a new process per pick
+    pub persistent_picker: bool,
+    /// Desktop notification with a swatch after each pick
+    pub notify_on_pick: bool,
+    /// Also set the PRIMARY selection (middle-click paste) on X11
+    pub copy_to_primary: bool,
+    /// Extra arguments appended to `yoinkctl daemon` in the autostart entry
+    pub daemon_args: Vec<String>,
+    /// Environment variables set for the daemon when it's autostarted
+    pub daemon_env: BTreeMap<String, String>,
+    /// ICC profile for monitors without their own entry in `monitor_icc_profiles`
+    pub icc_profile: Option<PathBuf>,
+    /// ICC profiles by monitor name, e.g. `"DP-1"`
+    pub monitor_icc_profiles: BTreeMap<String, PathBuf>,
+    /// Color space picked values are converted to when a monitor has a profile
+    pub managed_space: ManagedSpace,
+    /// What the picker overlay shows behind the magnifier
+    pub overlay_mode: OverlayMode,
+    pub theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlayMode {
+    /// Paint the screenshot taken at launch, so what you see is what gets sampled
+    #[default]
+    Freeze,
+    /// Show the live desktop and keep re-capturing the area around the cursor
+    Live,
+}
+
+/// Falls back to the default hotkey when the saved one doesn't parse, e.g. one
+/// written by an older version, instead of failing the whole config
+fn lenient_hotkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Hotkey, D::Error> {
+    let value = serde_json::Value::deserialize(deserializer)?;
+    let parsed = match &value {
+        serde_json::Value::String(text) => Hotkey::parse(text),
+        other => Err(format!("expected a string, got {}", other)),
+    };
+    
+    Ok(parsed.unwrap_or_else(|e| {
+        eprintln!("⚠️  Ignoring hotkey {} in config ({}), using {}", value, e, Hotkey::default());
+        Hotkey::default()
+    }))
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            hotkey: "Super+Shift+A".to_string(),
+            hotkey: Hotkey::default(),
             show_hex: true,
             show_rgb: true,
             show_hsl: true,
+            show_grid: true,
+            show_coordinates: true,
             preview_size: 120,
+            persistent_picker: false,
+            notify_on_pick: false,
+            copy_to_primary: false,
+            daemon_args: Vec::new(),
+            daemon_env: BTreeMap::new(),
+            icc_profile: None,
+            monitor_icc_profiles: BTreeMap::new(),
+            managed_space: ManagedSpace::default(),
+            overlay_mode: OverlayMode::default(),
+            theme: Theme::default(),
         }
     }
 }
@@ -56,69 +121,31 @@ impl Config {
             .map_err(|e| format!("Failed to write config: {}", e))
     }
     
-    pub fn get_modifiers(&self) -> Modifiers {
-        let mut modifiers = Modifiers::empty();
-        
-        if self.hotkey.contains("Super") {
-            modifiers |= Modifiers::SUPER;
-        }
-        if self.hotkey.contains("Shift") {
-            modifiers |= Modifiers::SHIFT;
-        }
-        if self.hotkey.contains("Ctrl") {
-            modifiers |= Modifiers::CONTROL;
-        }
-        if self.hotkey.contains("Alt") {
-            modifiers |= Modifiers::ALT;
-        }
-        
-        modifiers
+    /// Validates that the hotkey has at least one modifier
+    pub fn validate_hotkey(&self) -> Result<(), String> {
+        self.hotkey.validate()
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
     
-    pub fn get_key_code(&self) -> Code {
-        // Extract the key from the hotkey string
-        let parts: Vec<&str> = self.hotkey.split('+').collect();
-        let key = parts.last().unwrap_or(&"A").trim();
-        
-        // Map the key string to a Code
-        match key {
-            "A" => Code::KeyA,
-            "B" => Code::KeyB,
-            "C" => Code::KeyC,
-            "D" => Code::KeyD,
-            "E" => Code::KeyE,
-            "F" => Code::KeyF,
-            "G" => Code::KeyG,
-            "H" => Code::KeyH,
-            "I" => Code::KeyI,
-            "J" => Code::KeyJ,
-            "K" => Code::KeyK,
-            "L" => Code::KeyL,
-            "M" => Code::KeyM,
-            "N" => Code::KeyN,
-            "O" => Code::KeyO,
-            "P" => Code::KeyP,
-            "Q" => Code::KeyQ,
-            "R" => Code::KeyR,
-            "S" => Code::KeyS,
-            "T" => Code::KeyT,
-            "U" => Code::KeyU,
-            "V" => Code::KeyV,
-            "W" => Code::KeyW,
-            "X" => Code::KeyX,
-            "Y" => Code::KeyY,
-            "Z" => Code::KeyZ,
-            _ => Code::KeyA, // Default fallback
-        }
+    #[test]
+    fn saved_hotkey_is_kept() {
+        let config: Config = serde_json::from_str(r#"{"hotkey": "Ctrl+Alt+P"}"#).unwrap();
+        assert_eq!(config.hotkey, Hotkey::parse("Ctrl+Alt+P").unwrap());
     }
     
-    /// Validates that the hotkey has at least one modifier
-    pub fn validate_hotkey(&self) -> Result<(), String> {
-        let modifiers = self.get_modifiers();
-        if modifiers.is_empty() {
-            Err("Hotkey must have at least one modifier (Super, Shift, Ctrl, or Alt)".to_string())
-        } else {
-            Ok(())
+    #[test]
+    fn unknown_hotkey_falls_back_without_losing_the_rest() {
+        for hotkey in [r#""Hyper+Nope""#, r#""""#, "42", r#"{"key": "C"}"#] {
+            let text = format!(r#"{{"hotkey": {}, "preview_size": 200, "show_hex": false}}"#, hotkey);
+            let config: Config = serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {}", hotkey, e));
+            
+            assert_eq!(config.hotkey, Hotkey::default(), "{}", hotkey);
+            assert_eq!(config.preview_size, 200);
+            assert!(!config.show_hex);
         }
     }
-}
\ No newline at end of file
+}
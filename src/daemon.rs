@@ -0,0 +1,415 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Instant;
+use global_hotkey::{GlobalHotKeyManager, GlobalHotKeyEvent};
+
+use crate::clipboard;
+use crate::config::Config;
+use crate::diagnostics::{self, DaemonStatus};
+use crate::hotkey::Hotkey;
+use crate::ipc::{self, Request, Response};
+use crate::portal::{self, GlobalShortcuts, ShortcutSession};
+use crate::resident::{self, PickerHandle};
+
+enum DaemonEvent {
+    HotKey(GlobalHotKeyEvent),
+    /// A GlobalShortcuts portal activation, with the session it belongs to
+    Shortcut(String),
+    Control(Request, mpsc::Sender<Response>),
+    /// The resident picker window is gone, launch picker processes instead
+    ResidentGone,
+    Shutdown(i32),
+}
+
+struct Daemon {
+    manager: GlobalHotKeyManager,
+    /// Set when the hotkey is bound through the GlobalShortcuts portal instead of an X11 grab
+    shortcuts: Option<(GlobalShortcuts, ShortcutSession)>,
+    hotkey: Hotkey,
+    /// Copy of `hotkey` for status requests answered on the socket thread
+    shared_hotkey: Arc<Mutex<Hotkey>>,
+    exe_path: PathBuf,
+    last_activation: Instant,
+    resident: Option<PickerHandle>,
+    /// Set while a thread captures the screen for the resident picker
+    capturing: Arc<AtomicBool>,
+    sender: mpsc::Sender<DaemonEvent>,
+}
+
+pub fn run_daemon() -> Result<(), String> {
+    if let Ok(response) = ipc::send(Request::Status) {
+        println!("✅ yoinkctl daemon already running (pid {})", response.pid.unwrap_or_default());
+        return Ok(());
+    }
+
+    let config = Config::load().unwrap_or_default();
+
+    println!("🚀 yoinkctl daemon starting...");
+    println!("📌 Hotkey: {}", config.hotkey);
+
+    let (sender, events) = mpsc::channel();
+    forward_hotkey_events(sender.clone());
+    forward_signals(sender.clone())?;
+
+    let manager = GlobalHotKeyManager::new()
+        .map_err(|e| format!("Failed to create hotkey manager: {}", e))?;
+
+    config.validate_hotkey()?;
+
+    let shortcuts = if portal::should_use() {
+        match bind_portal_shortcut(&config.hotkey, sender.clone()) {
+            Ok(shortcuts) => Some(shortcuts),
+            Err(e) => {
+                eprintln!("⚠️  GlobalShortcuts portal unavailable ({}), falling back to an X11 grab", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    if shortcuts.is_none() {
+        register_hotkey(&manager, &config.hotkey)?;
+    }
+    println!("✅ Hotkey registered! Press {} to pick colors", config.hotkey);
+
+    let shared_hotkey = Arc::new(Mutex::new(config.hotkey));
+    serve_control_socket(sender.clone(), shared_hotkey.clone())?;
+    // Picks made by the resident picker, and by picker processes over the
+    // control socket, stay in the clipboard as long as the daemon runs
+    clipboard::keep_in_process();
+    DaemonStatus::running(&config.hotkey).save().ok();
+
+    let mut daemon = Daemon {
+        manager,
+        shortcuts,
+        hotkey: config.hotkey,
+        shared_hotkey,
+        exe_path: env::current_exe()
+            .map_err(|e| format!("Failed to get exe path: {}", e))?,
+        last_activation: Instant::now(),
+        resident: None,
+        capturing: Arc::new(AtomicBool::new(false)),
+        sender,
+    };
+
+    if !config.persistent_picker {
+        daemon.run(events);
+        return Ok(());
+    }
+
+    // eframe needs the main thread, so the event loop moves to a worker while
+    // the pre-warmed picker window waits here
+    let (handle, starter) = resident::channel();
+    daemon.resident = Some(handle);
+    let worker = std::thread::spawn(move || daemon.run(events));
+
+    if let Err(e) = starter.run() {
+        eprintln!("⚠️  Resident picker unavailable ({}), falling back to picker processes", e);
+    }
+
+    worker.join().ok();
+    Ok(())
+}
+
+impl Daemon {
+    fn run(mut self, events: mpsc::Receiver<DaemonEvent>) {
+        // This loop blocks until an event arrives. The X11 grab itself is still
+        // polled: global-hotkey's backend checks its connection every 50ms on
+        // its own thread. Only the portal path is free of wakeups
+        while let Ok(event) = events.recv() {
+            match event {
+                DaemonEvent::HotKey(event) => self.handle_hotkey(event),
+                DaemonEvent::Shortcut(session) => {
+                    let ours = self.shortcuts
+                        .as_ref()
+                        .is_some_and(|(_, current)| current.handle() == session);
+                    if ours {
+                        self.activate();
+                    }
+                }
+                DaemonEvent::Control(request, reply) => {
+                    let stop = request == Request::Stop;
+                    reply.send(self.handle_request(request)).ok();
+                    if stop {
+                        println!("🛑 Stop requested over control socket, shutting down");
+                        break;
+                    }
+                }
+                DaemonEvent::ResidentGone => self.resident = None,
+                DaemonEvent::Shutdown(signal) => {
+                    println!("🛑 Received signal {}, shutting down", signal);
+                    break;
+                }
+            }
+        }
+
+        // Dropping the portal session closes it
+        self.shortcuts = None;
+        self.manager.unregister(self.hotkey.to_global()).ok();
+        if let Some(resident) = &self.resident {
+            resident.quit();
+        }
+        ipc::remove_socket();
+        DaemonStatus::clear();
+        println!("👋 yoinkctl daemon stopped");
+    }
+
+    fn handle_hotkey(&mut self, event: GlobalHotKeyEvent) {
+        // Release events carry nothing for us, only presses trigger the picker
+        if event.state == global_hotkey::HotKeyState::Released {
+            return;
+        }
+
+        // Only process PRESSED events for our hotkey
+        if event.id != self.hotkey.to_global().id() {
+            return;
+        }
+
+        self.activate();
+    }
+
+    fn activate(&mut self) {
+        let now = Instant::now();
+
+        // Minimal debounce - just enough to prevent accidental double-press
+        if now.duration_since(self.last_activation).as_millis() < 50 {
+            return;
+        }
+
+        self.last_activation = now;
+        self.launch_picker(now);
+    }
+
+    fn handle_request(&mut self, request: Request) -> Response {
+        match request {
+            Request::Status | Request::Version | Request::Copy { .. } => {
+                answer_directly(&request, &self.shared_hotkey).unwrap_or_else(|| Response::error("unexpected request"))
+            }
+            Request::Pick => {
+                self.launch_picker(Instant::now());
+                Response::ok("picker launched")
+            }
+            Request::Reload => match self.reload() {
+                Ok(message) => Response {
+                    hotkey: Some(self.hotkey.to_string()),
+                    ..Response::ok(message)
+                },
+                Err(e) => Response::error(e),
+            },
+            Request::Stop => Response::ok("stopping"),
+        }
+    }
+
+    /// Re-reads the config and swaps the hotkey grab if it changed, keeping
+    /// the old one when the new combination can't be registered
+    fn reload(&mut self) -> Result<String, String> {
+        let config = Config::load()?;
+
+        if config.hotkey == self.hotkey {
+            return Ok("config reloaded".to_string());
+        }
+
+        config.validate_hotkey()?;
+
+        if let Some((shortcuts, session)) = &mut self.shortcuts {
+            // Bind a fresh session first, the old one keeps working if that fails
+            *session = shortcuts.bind(&config.hotkey)?;
+            if let Some(trigger) = session.trigger() {
+                println!("🔗 Compositor bound the shortcut to {}", trigger);
+            }
+        } else {
+            self.manager.unregister(self.hotkey.to_global()).ok();
+
+            if let Err(e) = register_hotkey(&self.manager, &config.hotkey) {
+                register_hotkey(&self.manager, &self.hotkey).ok();
+                return Err(e);
+            }
+        }
+
+        println!("🔁 Hotkey changed from {} to {}", self.hotkey, config.hotkey);
+        self.hotkey = config.hotkey;
+        *self.shared_hotkey.lock().unwrap_or_else(|e| e.into_inner()) = self.hotkey;
+        DaemonStatus::running(&self.hotkey).save().ok();
+
+        Ok(format!("hotkey is now {}", self.hotkey))
+    }
+
+    fn launch_picker(&mut self, requested: Instant) {
+        let Some(resident) = self.resident.clone() else {
+            spawn_picker_process(&self.exe_path);
+            return;
+        };
+
+        // A capture is already on its way to the resident picker
+        if self.capturing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        // Capturing can take a while, the loop keeps serving hotkeys and
+        // control requests meanwhile
+        let capturing = self.capturing.clone();
+        let sender = self.sender.clone();
+        let exe_path = self.exe_path.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = resident.show(requested) {
+                eprintln!("⚠️  {}, launching picker processes from now on", e);
+                sender.send(DaemonEvent::ResidentGone).ok();
+                spawn_picker_process(&exe_path);
+            }
+            capturing.store(false, Ordering::Release);
+        });
+    }
+}
+
+fn spawn_picker_process(exe_path: &Path) {
+    // Launch picker immediately - let the picker handle its own locking
+    // This prevents the daemon from being blocked by stale lock checks
+    Command::new(exe_path)
+        .arg("pick")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .ok();
+}
+
+/// Requests that don't touch the hotkey grab or the picker. They're answered
+/// on the socket thread, so `status` works while the loop is busy
+fn answer_directly(request: &Request, hotkey: &Mutex<Hotkey>) -> Option<Response> {
+    match request {
+        Request::Status => Some(Response {
+            pid: Some(std::process::id()),
+            hotkey: Some(hotkey.lock().unwrap_or_else(|e| e.into_inner()).to_string()),
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            ..Response::ok("running")
+        }),
+        Request::Version => Some(Response {
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            ..Response::ok(env!("CARGO_PKG_VERSION"))
+        }),
+        Request::Copy { text, primary } => Some(match clipboard::copy_in_process(text, *primary) {
+            Ok(()) => Response::ok("copied"),
+            Err(e) => Response::error(e),
+        }),
+        Request::Pick | Request::Reload | Request::Stop => None,
+    }
+}
+
+fn register_hotkey(manager: &GlobalHotKeyManager, hotkey: &Hotkey) -> Result<(), String> {
+    // Probe on a separate X connection first: a grab that conflicts with another
+    // application would otherwise abort the daemon from inside Xlib
+    if let Err(e) = diagnostics::probe_hotkey(hotkey) {
+        let suggestions: Vec<String> = diagnostics::suggest_alternatives(hotkey, 3)
+            .iter()
+            .map(|h| h.to_string())
+            .collect();
+
+        if suggestions.is_empty() {
+            return Err(format!("Hotkey conflict: {}", e));
+        }
+        return Err(format!("Hotkey conflict: {}. Free alternatives: {}", e, suggestions.join(", ")));
+    }
+
+    let global = hotkey.to_global();
+
+    // FORCE REGISTER: Try to unregister first, then register
+    let _ = manager.unregister(global);
+
+    if let Err(e) = manager.register(global) {
+        eprintln!("⚠️  First registration failed ({}), forcing...", e);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        let _ = manager.unregister(global);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        manager.register(global)
+            .map_err(|e| {
+                eprintln!("❌ Failed to force register hotkey '{}'", hotkey);
+                eprintln!("   Error: {}", e);
+                format!("Hotkey conflict: {}", e)
+            })?;
+
+        println!("✅ Hotkey forcefully registered!");
+    }
+
+    Ok(())
+}
+
+/// Binds the hotkey through the GlobalShortcuts portal, so it works on
+/// Wayland compositors that don't let X11 clients grab keys
+fn bind_portal_shortcut(hotkey: &Hotkey, sender: mpsc::Sender<DaemonEvent>) -> Result<(GlobalShortcuts, ShortcutSession), String> {
+    let shortcuts = GlobalShortcuts::connect()?;
+    shortcuts.listen(move |session| {
+        sender.send(DaemonEvent::Shortcut(session)).ok();
+    })?;
+
+    let session = shortcuts.bind(hotkey)?;
+    if let Some(trigger) = session.trigger() {
+        println!("🔗 Compositor bound the shortcut to {}", trigger);
+    }
+
+    Ok((shortcuts, session))
+}
+
+fn forward_hotkey_events(sender: mpsc::Sender<DaemonEvent>) {
+    // Must be installed before the first hotkey event is sent, global_hotkey
+    // only looks the handler up once
+    GlobalHotKeyEvent::set_event_handler(Some(move |event| {
+        sender.send(DaemonEvent::HotKey(event)).ok();
+    }));
+}
+
+fn forward_signals(sender: mpsc::Sender<DaemonEvent>) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+
+        let mut signals = signal_hook::iterator::Signals::new([SIGTERM, SIGINT, SIGHUP])
+            .map_err(|e| format!("Failed to install signal handlers: {}", e))?;
+
+        std::thread::spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                sender.send(DaemonEvent::Shutdown(signal)).ok();
+            }
+        });
+    }
+
+    #[cfg(not(unix))]
+    drop(sender);
+
+    Ok(())
+}
+
+/// Answers control socket requests, handing the ones that change the
+/// daemon's state to the main loop
+fn serve_control_socket(sender: mpsc::Sender<DaemonEvent>, hotkey: Arc<Mutex<Hotkey>>) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        let listener = ipc::bind()?;
+
+        std::thread::spawn(move || {
+            ipc::serve(listener, |request| {
+                if let Some(response) = answer_directly(&request, &hotkey) {
+                    return response;
+                }
+
+                let (reply, response) = mpsc::channel();
+                if sender.send(DaemonEvent::Control(request, reply)).is_err() {
+                    return Response::error("daemon is shutting down");
+                }
+                response
+                    .recv_timeout(std::time::Duration::from_secs(2))
+                    .unwrap_or_else(|_| Response::error("daemon did not answer"))
+            });
+        });
+    }
+
+    #[cfg(not(unix))]
+    drop((sender, hotkey));
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use global_hotkey::hotkey::{Code, Modifiers};
+use crate::hotkey::Hotkey;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DaemonState {
+    Running,
+    Failed,
+}
+
+/// Last known state of the daemon, written to disk so the GUI can show
+/// errors that would otherwise only reach the daemon's (discarded) stderr.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub hotkey: String,
+    pub state: DaemonState,
+    pub message: Option<String>,
+    pub timestamp: i64,
+}
+
+impl DaemonStatus {
+    pub fn running(hotkey: &Hotkey) -> Self {
+        Self {
+            pid: std::process::id(),
+            hotkey: hotkey.to_string(),
+            state: DaemonState::Running,
+            message: None,
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    pub fn failed(hotkey: &Hotkey, message: String) -> Self {
+        Self {
+            pid: std::process::id(),
+            hotkey: hotkey.to_string(),
+            state: DaemonState::Failed,
+            message: Some(message),
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    pub fn status_path() -> PathBuf {
+        let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("yoinkctl");
+        std::fs::create_dir_all(&path).ok();
+        path.push("daemon-status.json");
+        path
+    }
+
+    pub fn load() -> Result<Option<Self>, String> {
+        let path = Self::status_path();
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read daemon status: {}", e))?;
+
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse daemon status: {}", e))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::status_path();
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize daemon status: {}", e))?;
+
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write daemon status: {}", e))
+    }
+
+    /// Removes the status file, used when the daemon shuts down cleanly
+    pub fn clear() {
+        std::fs::remove_file(Self::status_path()).ok();
+    }
+}
+
+/// Tries to grab `hotkey` on its own X connection and releases it again.
+///
+/// `global_hotkey` doesn't install an X error handler, so a combination that
+/// is already grabbed by another client makes Xlib abort the whole process.
+/// Probing first turns that into a readable error.
+#[cfg(target_os = "linux")]
+pub fn probe_hotkey(hotkey: &Hotkey) -> Result<(), String> {
+    use std::ffi::CString;
+    use std::os::raw::c_int;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use x11_dl::xlib;
+
+    static GRAB_FAILED: AtomicBool = AtomicBool::new(false);
+
+    unsafe extern "C" fn on_x_error(_display: *mut xlib::Display, event: *mut xlib::XErrorEvent) -> c_int {
+        if (*event).error_code == xlib::BadAccess {
+            GRAB_FAILED.store(true, Ordering::SeqCst);
+        }
+        0
+    }
+
+    // Same lock-key variants global_hotkey grabs, so the probe matches what the daemon does
+    const IGNORED_MODS: [u32; 4] = [
+        0,
+        xlib::Mod2Mask,
+        xlib::LockMask,
+        xlib::Mod2Mask | xlib::LockMask,
+    ];
+
+    let keysym_name = x11_keysym_name(hotkey.code)
+        .ok_or_else(|| format!("{} has no X11 equivalent", crate::hotkey::key_name(hotkey.code)))?;
+    let keysym_name = CString::new(keysym_name).map_err(|e| e.to_string())?;
+
+    let mut modifiers = 0;
+    if hotkey.modifiers.contains(Modifiers::SHIFT) {
+        modifiers |= xlib::ShiftMask;
+    }
+    if hotkey.modifiers.contains(Modifiers::CONTROL) {
+        modifiers |= xlib::ControlMask;
+    }
+    if hotkey.modifiers.contains(Modifiers::ALT) {
+        modifiers |= xlib::Mod1Mask;
+    }
+    if hotkey.modifiers.contains(Modifiers::SUPER) {
+        modifiers |= xlib::Mod4Mask;
+    }
+
+    let xlib = xlib::Xlib::open()
+        .map_err(|e| format!("Failed to load Xlib: {}", e))?;
+
+    unsafe {
+        let display = (xlib.XOpenDisplay)(std::ptr::null());
+        if display.is_null() {
+            return Err("Cannot open X display (is this an X11 session?)".to_string());
+        }
+
+        let root = (xlib.XDefaultRootWindow)(display);
+        let keysym = (xlib.XStringToKeysym)(keysym_name.as_ptr());
+        let keycode = (xlib.XKeysymToKeycode)(display, keysym);
+
+        if keycode == 0 {
+            (xlib.XCloseDisplay)(display);
+            return Err(format!("No key on this keyboard produces {}", crate::hotkey::key_name(hotkey.code)));
+        }
+
+        GRAB_FAILED.store(false, Ordering::SeqCst);
+        let previous_handler = (xlib.XSetErrorHandler)(Some(on_x_error));
+
+        for m in IGNORED_MODS {
+            (xlib.XGrabKey)(display, keycode as _, modifiers | m, root, 0, xlib::GrabModeAsync, xlib::GrabModeAsync);
+        }
+        (xlib.XSync)(display, xlib::False);
+
+        let taken = GRAB_FAILED.load(Ordering::SeqCst);
+
+        for m in IGNORED_MODS {
+            (xlib.XUngrabKey)(display, keycode as _, modifiers | m, root);
+        }
+        (xlib.XSync)(display, xlib::False);
+
+        (xlib.XSetErrorHandler)(previous_handler);
+        (xlib.XCloseDisplay)(display);
+
+        if taken {
+            return Err(format!("{} is already taken by another application", hotkey));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn probe_hotkey(_hotkey: &Hotkey) -> Result<(), String> {
+    Ok(())
+}
+
+/// Finds up to `count` combinations close to `taken` that can currently be grabbed.
+pub fn suggest_alternatives(taken: &Hotkey, count: usize) -> Vec<Hotkey> {
+    let modifier_sets = [
+        taken.modifiers,
+        Modifiers::SUPER | Modifiers::SHIFT,
+        Modifiers::CONTROL | Modifiers::ALT,
+        Modifiers::SUPER | Modifiers::ALT,
+        Modifiers::CONTROL | Modifiers::SHIFT,
+        Modifiers::SUPER | Modifiers::CONTROL,
+    ];
+    let codes = [taken.code, Code::KeyC, Code::KeyP, Code::KeyX, Code::KeyK, Code::F9, Code::PrintScreen];
+
+    let mut suggestions = Vec::with_capacity(count);
+
+    for code in codes {
+        for modifiers in modifier_sets {
+            if suggestions.len() >= count {
+                return suggestions;
+            }
+
+            let candidate = Hotkey::new(modifiers, code);
+            if candidate == *taken || modifiers.is_empty() || suggestions.contains(&candidate) {
+                continue;
+            }
+
+            if probe_hotkey(&candidate).is_ok() {
+                suggestions.push(candidate);
+            }
+        }
+    }
+
+    suggestions
+}
+
+pub fn session_type() -> String {
+    std::env::var("XDG_SESSION_TYPE").unwrap_or_else(|_| {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            "wayland".to_string()
+        } else if std::env::var_os("DISPLAY").is_some() {
+            "x11".to_string()
+        } else {
+            "unknown".to_string()
+        }
+    })
+}
+
+/// Keysym name as understood by XStringToKeysym (and xkbcommon)
+#[cfg(target_os = "linux")]
+pub fn x11_keysym_name(code: Code) -> Option<String> {
+    let name = code.to_string();
+
+    if let Some(letter) = name.strip_prefix("Key") {
+        return Some(letter.to_lowercase());
+    }
+    if let Some(digit) = name.strip_prefix("Digit") {
+        return Some(digit.to_string());
+    }
+    if let Some(digit) = name.strip_prefix("Numpad").filter(|d| d.len() == 1) {
+        return Some(format!("KP_{}", digit));
+    }
+    if name.len() <= 3 && name.starts_with('F') {
+        return Some(name);
+    }
+
+    let keysym = match code {
+        Code::Backquote => "grave",
+        Code::Minus => "minus",
+        Code::Equal => "equal",
+        Code::BracketLeft => "bracketleft",
+        Code::BracketRight => "bracketright",
+        Code::Backslash => "backslash",
+        Code::Semicolon => "semicolon",
+        Code::Quote => "apostrophe",
+        Code::Comma => "comma",
+        Code::Period => "period",
+        Code::Slash => "slash",
+        Code::PrintScreen => "Print",
+        Code::Escape => "Escape",
+        Code::Enter => "Return",
+        Code::Space => "space",
+        Code::Tab => "Tab",
+        Code::Backspace => "BackSpace",
+        Code::Insert => "Insert",
+        Code::Delete => "Delete",
+        Code::Home => "Home",
+        Code::End => "End",
+        Code::PageUp => "Prior",
+        Code::PageDown => "Next",
+        Code::ArrowUp => "Up",
+        Code::ArrowDown => "Down",
+        Code::ArrowLeft => "Left",
+        Code::ArrowRight => "Right",
+        Code::Pause => "Pause",
+        Code::ScrollLock => "Scroll_Lock",
+        Code::NumLock => "Num_Lock",
+        Code::CapsLock => "Caps_Lock",
+        Code::NumpadAdd => "KP_Add",
+        Code::NumpadSubtract => "KP_Subtract",
+        Code::NumpadMultiply => "KP_Multiply",
+        Code::NumpadDivide => "KP_Divide",
+        Code::NumpadDecimal => "KP_Decimal",
+        Code::NumpadEnter => "KP_Enter",
+        Code::NumpadEqual => "KP_Equal",
+        Code::AudioVolumeUp => "XF86AudioRaiseVolume",
+        Code::AudioVolumeDown => "XF86AudioLowerVolume",
+        Code::AudioVolumeMute => "XF86AudioMute",
+        Code::MediaPlayPause => "XF86AudioPlay",
+        Code::MediaStop => "XF86AudioStop",
+        Code::MediaTrackNext => "XF86AudioNext",
+        Code::MediaTrackPrevious => "XF86AudioPrev",
+        _ => return None,
+    };
+
+    Some(keysym.to_string())
+}
\ No newline at end of file
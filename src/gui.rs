@@ -1,36 +1,130 @@
 use eframe::egui;
 use std::env;
 use std::process::Command;
-use arboard::Clipboard;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use global_hotkey::hotkey::Modifiers;
 
-use crate::config::Config;
-use crate::autostart::Autostart;
-use crate::history::ColorHistory;
+use crate::clipboard;
+use crate::config::{Config, OverlayMode};
+use crate::theme::{Palette, Theme, ThemeMode};
+use crate::autostart::{self, Autostart, AutostartBackend};
+use crate::harmony::{self, Scheme};
+use crate::history::{ColorHistory, NamedPalette};
+use crate::notation::{self, Format, ParsedColor, SliderSpace};
+use crate::palette::PaletteColor;
+use crate::hotkey::Hotkey;
+use crate::icc::{self, ManagedSpace};
+use crate::diagnostics::{self, DaemonState, DaemonStatus};
+use crate::ipc::{self, Request};
+
+/// A generated color and the caption shown under it, e.g. its shade step
+type Swatch = (Option<String>, (u8, u8, u8));
+
+/// A hotkey check and the free alternatives found alongside it
+type HotkeyProbe = (HotkeyCheck, Vec<Hotkey>);
+
+/// Outcome of "Check availability"
+enum HotkeyCheck {
+    Checking,
+    Free(String),
+    Taken(String),
+    /// Nothing to probe, e.g. on Wayland without XWayland
+    Unknown(String),
+}
 
 pub struct ConfigApp {
     config: Config,
     daemon_running: bool,
+    /// Kept up to date by [`poll_daemon`], so a slow daemon never stalls a frame
+    daemon_poll: Arc<AtomicBool>,
     save_message: Option<(String, std::time::Instant)>,
     autostart: Autostart,
+    autostart_backend: AutostartBackend,
+    systemd_available: bool,
+    service_state: Option<String>,
+    daemon_args_text: String,
+    daemon_env_text: String,
+    daemon_launch_error: Option<String>,
+    icc_profile_text: String,
     history: ColorHistory,
     show_settings_window: bool,
+    show_converter_window: bool,
+    converter_text: String,
+    converter_color: ParsedColor,
+    converter_error: Option<String>,
+    converter_space: SliderSpace,
+    /// Slider values in `converter_space`, kept separately so e.g. the hue
+    /// of a gray survives dragging saturation down to zero and back
+    converter_channels: [f32; 3],
     copy_message: Option<(String, std::time::Instant)>,
     hovered_index: Option<usize>,
+    /// History color the harmony and shade generator is showing
+    harmony_base: Option<(u8, u8, u8)>,
     last_history_reload: std::time::Instant,
+    recording_hotkey: bool,
+    hotkey_text: String,
+    hotkey_error: Option<String>,
+    daemon_status: Option<DaemonStatus>,
+    hotkey_check: Option<HotkeyCheck>,
+    /// Answer from the thread probing the hotkey, and which hotkey it probed
+    hotkey_check_result: Option<(Hotkey, mpsc::Receiver<HotkeyProbe>)>,
+    hotkey_suggestions: Vec<Hotkey>,
+    /// Colors for the current theme, refreshed every frame
+    palette: Palette,
+    /// Theme last handed to egui, so edits in Settings preview right away
+    applied_theme: Option<Theme>,
 }
 
 impl ConfigApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let config = Config::load().unwrap_or_default();
+        let palette = Palette::new(&config.theme, true);
+        let autostart = Autostart::new();
+        
+        // Entries written by an older build or from inside an AppImage mount
+        // stop working once that path disappears
+        let save_message = match autostart.repair(&config) {
+            Ok(true) => Some(("Autostart entry repaired".to_string(), std::time::Instant::now())),
+            Ok(false) => None,
+            Err(e) => Some((format!("Autostart repair failed: {}", e), std::time::Instant::now())),
+        };
+        let daemon_poll = poll_daemon(cc.egui_ctx.clone());
+        
         Self {
-            config: Config::load().unwrap_or_default(),
-            daemon_running: is_daemon_running(),
-            save_message: None,
-            autostart: Autostart::new(),
+            hotkey_text: config.hotkey.to_string(),
+            daemon_args_text: autostart::join_args(&config.daemon_args),
+            daemon_env_text: autostart::format_env(&config.daemon_env),
+            daemon_launch_error: None,
+            icc_profile_text: config.icc_profile.as_ref().map(|path| path.display().to_string()).unwrap_or_default(),
+            config,
+            daemon_running: daemon_poll.load(Ordering::Relaxed),
+            daemon_poll,
+            save_message,
+            autostart_backend: autostart.active_backend().unwrap_or(AutostartBackend::DesktopFile),
+            autostart,
+            systemd_available: autostart::systemd_available(),
+            service_state: None,
             history: ColorHistory::load().unwrap_or_default(),
             show_settings_window: false,
+            show_converter_window: false,
+            converter_text: String::new(),
+            converter_color: ParsedColor::opaque((255, 255, 255)),
+            converter_error: None,
+            converter_space: SliderSpace::Rgb,
+            converter_channels: [255.0; 3],
             copy_message: None,
             hovered_index: None,
+            harmony_base: None,
             last_history_reload: std::time::Instant::now(),
+            recording_hotkey: false,
+            hotkey_error: None,
+            daemon_status: DaemonStatus::load().ok().flatten(),
+            hotkey_check: None,
+            hotkey_check_result: None,
+            hotkey_suggestions: Vec::new(),
+            palette,
+            applied_theme: None,
         }
     }
     
@@ -40,6 +134,10 @@ impl ConfigApp {
             if let Ok(history) = ColorHistory::load() {
                 self.history = history;
             }
+            
+            self.daemon_status = DaemonStatus::load().ok().flatten();
+            self.daemon_running = self.daemon_poll.load(Ordering::Relaxed);
+            
             self.last_history_reload = now;
         }
     }
@@ -58,13 +156,124 @@ impl ConfigApp {
         }
     }
     
+    /// Copies through the daemon or clipboard helper, so closing the app keeps the value
+    fn copy(&mut self, text: &str) {
+        let message = match clipboard::copy_text(text, self.config.copy_to_primary) {
+            Ok(()) => format!("Copied {}!", text),
+            Err(e) => format!("Copy failed: {}", e),
+        };
+        self.copy_message = Some((message, std::time::Instant::now()));
+    }
+    
+    fn daemon_failure(&self) -> Option<String> {
+        self.daemon_status
+            .as_ref()
+            .filter(|status| status.state == DaemonState::Failed)
+            .and_then(|status| status.message.clone())
+    }
+    
+    /// Copies the profile path field into the config, checking the file first
+    fn apply_icc_profile(&mut self) -> Result<(), String> {
+        let text = self.icc_profile_text.trim();
+        if text.is_empty() {
+            self.config.icc_profile = None;
+            return Ok(());
+        }
+        
+        let path = std::path::PathBuf::from(text);
+        icc::check_profile(&path)?;
+        self.config.icc_profile = Some(path);
+        Ok(())
+    }
+    
+    fn refresh_service_state(&mut self) {
+        self.service_state = if self.autostart.active_backend() == Some(AutostartBackend::Systemd) {
+            autostart::systemd_status()
+        } else {
+            None
+        };
+    }
+    
+    fn set_autostart(&mut self, enabled: bool) {
+        let result = if enabled {
+            self.autostart.enable(self.autostart_backend, &self.config)
+        } else {
+            self.autostart.disable()
+        };
+        
+        let message = match result {
+            Ok(()) if enabled => format!("Autostart enabled ({})!", self.autostart_backend.label()),
+            Ok(()) => "Autostart disabled!".to_string(),
+            Err(e) => format!("Autostart error: {}", e),
+        };
+        self.save_message = Some((message, std::time::Instant::now()));
+        self.refresh_service_state();
+    }
+    
+    /// Regenerates the active autostart entry so changed arguments take effect
+    fn rewrite_autostart(&self) -> Result<(), String> {
+        match self.autostart.active_backend() {
+            Some(backend) => self.autostart.enable(backend, &self.config),
+            None => Ok(()),
+        }
+    }
+    
+    fn parse_daemon_launch(&mut self) {
+        let parsed = autostart::split_args(&self.daemon_args_text)
+            .map_err(|e| format!("Arguments: {}", e))
+            .and_then(|args| {
+                autostart::parse_env(&self.daemon_env_text)
+                    .map(|env| (args, env))
+                    .map_err(|e| format!("Environment: {}", e))
+            });
+        
+        match parsed {
+            Ok((args, env)) => {
+                self.config.daemon_args = args;
+                self.config.daemon_env = env;
+                self.daemon_launch_error = None;
+            }
+            Err(e) => self.daemon_launch_error = Some(e),
+        }
+    }
+    
+    /// Probes the hotkey on a background thread, every candidate for the
+    /// suggestions is an X round trip
+    fn check_hotkey(&mut self) {
+        let hotkey = self.config.hotkey;
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            sender.send(probe_hotkey(hotkey)).ok();
+        });
+        
+        self.hotkey_check = Some(HotkeyCheck::Checking);
+        self.hotkey_suggestions.clear();
+        self.hotkey_check_result = Some((hotkey, receiver));
+    }
+    
+    fn receive_hotkey_check(&mut self) {
+        let Some((hotkey, receiver)) = &self.hotkey_check_result else {
+            return;
+        };
+        let Ok((check, suggestions)) = receiver.try_recv() else {
+            return;
+        };
+        
+        // Editing the hotkey meanwhile drops the check
+        if *hotkey == self.config.hotkey && matches!(self.hotkey_check, Some(HotkeyCheck::Checking)) {
+            self.hotkey_check = Some(check);
+            self.hotkey_suggestions = suggestions;
+        }
+        self.hotkey_check_result = None;
+    }
+    
     fn render_header(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             ui.vertical_centered(|ui| {
-                ui.set_width(ui.available_width() - 60.0);
+                ui.set_width(ui.available_width() - 110.0);
                 ui.label(egui::RichText::new("yoinkctl").size(28.0).strong());
                 ui.add_space(4.0);
-                ui.label(egui::RichText::new("Color Picker").size(14.0).color(egui::Color32::GRAY));
+                ui.label(egui::RichText::new("Color Picker").size(14.0).color(self.palette.muted));
             });
             
             ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
@@ -72,10 +281,24 @@ impl ConfigApp {
                 if ui.add_sized(
                     [40.0, 40.0],
                     egui::Button::new(egui::RichText::new("⚙").size(20.0))
-                        .fill(egui::Color32::from_rgb(28, 28, 32))
+                        .fill(self.palette.card)
                         .rounding(8.0)
                 ).clicked() {
                     self.show_settings_window = !self.show_settings_window;
+                    self.refresh_service_state();
+                }
+                
+                if ui.add_sized(
+                    [40.0, 40.0],
+                    egui::Button::new(egui::RichText::new("⇄").size(20.0))
+                        .fill(self.palette.card)
+                        .rounding(8.0)
+                ).on_hover_text("Color converter").clicked() {
+                    self.show_converter_window = !self.show_converter_window;
+                    if self.converter_text.is_empty() {
+                        let start = self.history.entries.first().map(|entry| entry.hex.clone()).unwrap_or_else(|| "#FFFFFF".to_string());
+                        self.set_converter_text(start);
+                    }
                 }
             });
         });
@@ -87,7 +310,7 @@ impl ConfigApp {
             egui::Layout::top_down(egui::Align::Min),
             |ui| {
                 egui::Frame::none()
-                    .fill(egui::Color32::from_rgb(28, 28, 32))
+                    .fill(self.palette.card)
                     .rounding(12.0)
                     .inner_margin(20.0)
                     .show(ui, |ui| {
@@ -101,15 +324,24 @@ impl ConfigApp {
                                     .color(egui::Color32::from_rgb(74, 222, 128))
                             );
                             ui.add_space(6.0);
-                            ui.label(egui::RichText::new(&self.config.hotkey).size(12.0).color(egui::Color32::GRAY));
+                            ui.label(egui::RichText::new(self.config.hotkey.to_string()).size(12.0).color(self.palette.muted));
+                        } else if let Some(message) = self.daemon_failure() {
+                            ui.label(
+                                egui::RichText::new("⚠ Failed")
+                                    .size(13.0)
+                                    .color(egui::Color32::from_rgb(239, 68, 68))
+                            );
+                            ui.add_space(6.0);
+                            ui.label(egui::RichText::new("Hover for details").size(12.0).color(self.palette.muted))
+                                .on_hover_text(message);
                         } else {
                             ui.label(
                                 egui::RichText::new("○ Stopped")
                                     .size(13.0)
-                                    .color(egui::Color32::GRAY)
+                                    .color(self.palette.muted)
                             );
                             ui.add_space(6.0);
-                            ui.label(egui::RichText::new("Enable hotkey").size(12.0).color(egui::Color32::GRAY));
+                            ui.label(egui::RichText::new("Enable hotkey").size(12.0).color(self.palette.muted));
                         }
                         
                         ui.add_space(8.0);
@@ -122,8 +354,11 @@ impl ConfigApp {
                                         .fill(egui::Color32::from_rgb(239, 68, 68))
                                         .rounding(8.0)
                                 ).clicked() {
-                                    stop_daemon();
-                                    self.daemon_running = false;
+                                    match stop_daemon(&self.autostart) {
+                                        Ok(()) => self.daemon_running = false,
+                                        Err(e) => self.save_message = Some((format!("Failed to stop the daemon: {}", e), std::time::Instant::now())),
+                                    }
+                                    self.refresh_service_state();
                                 }
                             } else {
                                 if ui.add_sized(
@@ -132,8 +367,11 @@ impl ConfigApp {
                                         .fill(egui::Color32::from_rgb(59, 130, 246))
                                         .rounding(8.0)
                                 ).clicked() {
-                                    start_daemon();
-                                    self.daemon_running = true;
+                                    match start_daemon(&self.autostart) {
+                                        Ok(()) => self.daemon_running = true,
+                                        Err(e) => self.save_message = Some((format!("Failed to start the daemon: {}", e), std::time::Instant::now())),
+                                    }
+                                    self.refresh_service_state();
                                 }
                             }
                         });
@@ -148,13 +386,13 @@ impl ConfigApp {
             egui::Layout::top_down(egui::Align::Min),
             |ui| {
                 egui::Frame::none()
-                    .fill(egui::Color32::from_rgb(28, 28, 32))
+                    .fill(self.palette.card)
                     .rounding(12.0)
                     .inner_margin(20.0)
                     .show(ui, |ui| {
                         ui.label(egui::RichText::new("Quick Launch").size(16.0).strong());
                         ui.add_space(8.0);
-                        ui.label(egui::RichText::new("Test without hotkey").size(12.0).color(egui::Color32::GRAY));
+                        ui.label(egui::RichText::new("Test without hotkey").size(12.0).color(self.palette.muted));
                         ui.add_space(8.0);
                         
                         ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
@@ -232,21 +470,24 @@ impl ConfigApp {
                                 egui::Sense::hover()
                             );
                             ui.painter().rect_filled(rect, 2.0, color);
-                            ui.painter().rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::from_gray(60)));
+                            ui.painter().rect_stroke(rect, 2.0, egui::Stroke::new(1.0, self.palette.divider));
                             
                             ui.add_space(12.0);
                             
                             ui.label(egui::RichText::new(&entry.hex)
                                 .size(13.0)
-                                .color(egui::Color32::from_rgb(200, 200, 255))
+                                .color(self.palette.code)
                                 .family(egui::FontFamily::Monospace));
                             
                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                                 ui.add_space(20.0);
                                 ui.label(egui::RichText::new("│")
                                     .size(12.0)
-                                    .color(egui::Color32::from_gray(60))
+                                    .color(self.palette.divider)
                                     .family(egui::FontFamily::Monospace));
+                                if ui.small_button("◐").on_hover_text("Harmonies & shades").clicked() {
+                                    self.harmony_base = Some(entry.rgb);
+                                }
                             });
                         });
                     });
@@ -258,11 +499,7 @@ impl ConfigApp {
         }
         
         if response.clicked() {
-            if let Ok(mut clipboard) = Clipboard::new() {
-                if clipboard.set_text(&entry.hex).is_ok() {
-                    self.copy_message = Some((format!("Copied {}!", entry.hex), std::time::Instant::now()));
-                }
-            }
+            self.copy(&entry.hex);
         }
         
         if self.hovered_index == Some(idx) {
@@ -271,11 +508,156 @@ impl ConfigApp {
                 egui::Align2::LEFT_CENTER,
                 "<- click to copy",
                 egui::FontId::new(11.0, egui::FontFamily::Monospace),
-                egui::Color32::from_rgb(100, 255, 100),
+                self.palette.accent,
             );
         }
     }
     
+    /// One saved palette: its name and a strip with a segment per color,
+    /// as wide as the color's coverage
+    fn render_palette_strip(&mut self, ui: &mut egui::Ui, palette: &NamedPalette) {
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
+            ui.label(egui::RichText::new(format!("│ {}", palette.name))
+                .size(12.0)
+                .color(self.palette.muted)
+                .family(egui::FontFamily::Monospace));
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.add_space(20.0);
+                if ui.small_button("✕").on_hover_text("Delete palette").clicked() {
+                    self.history.remove_palette(&palette.name);
+                }
+            });
+        });
+        
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
+            
+            let width = ui.available_width() - 20.0;
+            let (strip, _) = ui.allocate_exact_size(egui::vec2(width, 22.0), egui::Sense::hover());
+            let total: f32 = palette.colors.iter().map(|color| color.coverage).sum::<f32>().max(f32::EPSILON);
+            
+            let mut left = strip.left();
+            for color in &palette.colors {
+                let segment = egui::Rect::from_min_max(
+                    egui::pos2(left, strip.top()),
+                    egui::pos2(left + strip.width() * color.coverage / total, strip.bottom()),
+                );
+                left = segment.right();
+                
+                let response = ui.interact(segment, ui.id().with((&palette.name, &color.hex)), egui::Sense::click());
+                ui.painter().rect_filled(segment, 0.0, egui::Color32::from_rgb(color.rgb.0, color.rgb.1, color.rgb.2));
+                
+                let response = response.on_hover_text(format!("{}  {:.1}%", color.hex, color.coverage * 100.0));
+                if response.clicked() {
+                    self.copy(&color.hex);
+                }
+            }
+            
+            ui.painter().rect_stroke(strip, 2.0, egui::Stroke::new(1.0, self.palette.divider));
+        });
+        
+        ui.add_space(8.0);
+    }
+    
+    /// Swatches that copy their hex when clicked, with optional captions below
+    fn render_swatch_row(&mut self, ui: &mut egui::Ui, swatches: &[Swatch]) {
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
+            ui.spacing_mut().item_spacing.x = 4.0;
+            
+            for (caption, rgb) in swatches {
+                let hex = crate::color::hex_string(*rgb, None);
+                ui.vertical(|ui| {
+                    let (rect, response) = ui.allocate_exact_size(egui::vec2(34.0, 22.0), egui::Sense::click());
+                    ui.painter().rect_filled(rect, 2.0, egui::Color32::from_rgb(rgb.0, rgb.1, rgb.2));
+                    ui.painter().rect_stroke(rect, 2.0, egui::Stroke::new(1.0, self.palette.divider));
+                    
+                    if response.on_hover_text(&hex).clicked() {
+                        self.copy(&hex);
+                    }
+                    
+                    if let Some(caption) = caption {
+                        ui.label(egui::RichText::new(caption)
+                            .size(9.0)
+                            .color(self.palette.faint)
+                            .family(egui::FontFamily::Monospace));
+                    }
+                });
+            }
+        });
+    }
+    
+    /// Saves generated colors as a palette, each with an equal share
+    fn save_generated_palette(&mut self, name: String, colors: &[(u8, u8, u8)]) {
+        let colors = colors
+            .iter()
+            .map(|&rgb| PaletteColor {
+                hex: crate::color::hex_string(rgb, None),
+                rgb,
+                coverage: 1.0 / colors.len() as f32,
+            })
+            .collect();
+        
+        self.copy_message = Some(match self.history.add_palette(name.clone(), colors) {
+            Ok(()) => (format!("Saved palette {}", name), std::time::Instant::now()),
+            Err(e) => (e, std::time::Instant::now()),
+        });
+    }
+    
+    /// Harmony schemes and a 50–950 shade scale for the chosen history color
+    fn render_harmonies(&mut self, ui: &mut egui::Ui, base: (u8, u8, u8)) {
+        let base_hex = crate::color::hex_string(base, None);
+        
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
+            ui.label(egui::RichText::new(format!("│ Harmonies for {}", base_hex))
+                .size(12.0)
+                .color(self.palette.muted)
+                .family(egui::FontFamily::Monospace));
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.add_space(20.0);
+                if ui.small_button("✕").on_hover_text("Close").clicked() {
+                    self.harmony_base = None;
+                }
+            });
+        });
+        ui.add_space(4.0);
+        
+        let mut rows: Vec<(String, Vec<Swatch>)> = Scheme::ALL
+            .iter()
+            .map(|scheme| (scheme.label().to_string(), scheme.colors(base).into_iter().map(|rgb| (None, rgb)).collect()))
+            .collect();
+        rows.push((
+            "Shades".to_string(),
+            harmony::shades(base).into_iter().map(|(step, rgb)| (Some(step.to_string()), rgb)).collect(),
+        ));
+        
+        for (label, swatches) in rows {
+            ui.horizontal(|ui| {
+                ui.add_space(20.0);
+                ui.label(egui::RichText::new(&label)
+                    .size(11.0)
+                    .color(self.palette.faint)
+                    .family(egui::FontFamily::Monospace));
+                
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add_space(20.0);
+                    if ui.small_button("Save").on_hover_text("Save as palette").clicked() {
+                        let colors: Vec<_> = swatches.iter().map(|(_, rgb)| *rgb).collect();
+                        self.save_generated_palette(format!("{} {}", base_hex, label.to_lowercase()), &colors);
+                    }
+                });
+            });
+            self.render_swatch_row(ui, &swatches);
+            ui.add_space(6.0);
+        }
+        
+        ui.add_space(8.0);
+    }
+    
     fn render_history_card(&mut self, ui: &mut egui::Ui, remaining_height: f32, margin: f32, content_width: f32) {
         ui.horizontal(|ui| {
             ui.add_space(margin);
@@ -285,10 +667,10 @@ impl ConfigApp {
                 egui::Layout::top_down(egui::Align::Min),
                 |ui| {
                     egui::Frame::none()
-                        .fill(egui::Color32::from_rgb(12, 12, 14))
+                        .fill(self.palette.inset)
                         .rounding(12.0)
                         .inner_margin(0.0)
-                        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(40, 40, 44)))
+                        .stroke(egui::Stroke::new(1.0, self.palette.border))
                         .show(ui, |ui| {
                             ui.add_space(16.0);
                             
@@ -296,7 +678,7 @@ impl ConfigApp {
                                 ui.add_space(20.0);
                                 ui.label(egui::RichText::new("┌─ [Color History] ─┐")
                                     .size(14.0)
-                                    .color(egui::Color32::from_rgb(100, 255, 100))
+                                    .color(self.palette.accent)
                                     .family(egui::FontFamily::Monospace));
                                 
                                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -317,13 +699,22 @@ impl ConfigApp {
                                 .show(ui, |ui| {
                                     self.hovered_index = None;
                                     
+                                    if let Some(base) = self.harmony_base {
+                                        self.render_harmonies(ui, base);
+                                    }
+                                    
+                                    let palettes: Vec<_> = self.history.palettes.clone();
+                                    for palette in &palettes {
+                                        self.render_palette_strip(ui, palette);
+                                    }
+                                    
                                     if self.history.entries.is_empty() {
                                         ui.add_space(8.0);
                                         ui.horizontal(|ui| {
                                             ui.add_space(20.0);
                                             ui.label(egui::RichText::new("│ No colors picked yet")
                                                 .size(12.0)
-                                                .color(egui::Color32::from_gray(100))
+                                                .color(self.palette.faint)
                                                 .family(egui::FontFamily::Monospace));
                                         });
                                         ui.add_space(8.0);
@@ -331,9 +722,9 @@ impl ConfigApp {
                                         let entries: Vec<_> = self.history.entries.clone();
                                         for (idx, entry) in entries.iter().enumerate() {
                                             let bg_color = if idx % 2 == 0 {
-                                                egui::Color32::from_rgb(16, 16, 18)
+                                                self.palette.inset_alt
                                             } else {
-                                                egui::Color32::from_rgb(12, 12, 14)
+                                                self.palette.inset
                                             };
                                             self.render_history_entry(ui, idx, entry, bg_color);
                                         }
@@ -346,7 +737,7 @@ impl ConfigApp {
                                 ui.add_space(20.0);
                                 ui.label(egui::RichText::new("└───────────────────┘")
                                     .size(14.0)
-                                    .color(egui::Color32::from_rgb(100, 255, 100))
+                                    .color(self.palette.accent)
                                     .family(egui::FontFamily::Monospace));
                             });
                             
@@ -356,7 +747,7 @@ impl ConfigApp {
                                     ui.add_space(20.0);
                                     ui.label(egui::RichText::new(msg)
                                         .size(11.0)
-                                        .color(egui::Color32::from_rgb(100, 255, 100))
+                                        .color(self.palette.accent)
                                         .family(egui::FontFamily::Monospace));
                                 });
                             }
@@ -370,6 +761,180 @@ impl ConfigApp {
         });
     }
     
+    fn record_hotkey(&mut self, ctx: &egui::Context) {
+        if !self.recording_hotkey {
+            return;
+        }
+        
+        let pressed = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key { key, physical_key, pressed: true, modifiers, .. } => {
+                    Some((physical_key.unwrap_or(*key), *modifiers))
+                }
+                _ => None,
+            })
+        });
+        
+        let Some((key, modifiers)) = pressed else {
+            return;
+        };
+        
+        if key == egui::Key::Escape && modifiers.is_none() {
+            self.recording_hotkey = false;
+            return;
+        }
+        
+        let with_super = self.config.hotkey.modifiers.contains(Modifiers::SUPER);
+        match Hotkey::from_egui(key, modifiers, with_super) {
+            Some(hotkey) => {
+                self.config.hotkey = hotkey;
+                self.hotkey_text = hotkey.to_string();
+                self.hotkey_error = None;
+                self.hotkey_check = None;
+            }
+            None => {
+                self.hotkey_error = Some(format!("Key '{}' can't be used as a hotkey", key.name()));
+            }
+        }
+        self.recording_hotkey = false;
+    }
+    
+    /// Parses typed or pasted text, keeping the last good color on errors
+    fn set_converter_text(&mut self, text: String) {
+        match notation::parse(&text) {
+            Ok(color) => {
+                self.converter_color = color;
+                self.converter_channels = self.converter_space.decompose(color.rgb);
+                self.converter_error = None;
+            }
+            Err(e) => self.converter_error = Some(e),
+        }
+        self.converter_text = text;
+    }
+    
+    fn draw_converter_window(&mut self, ctx: &egui::Context) {
+        if !self.show_converter_window {
+            return;
+        }
+        
+        let converter_id = egui::ViewportId::from_hash_of("converter_window");
+        
+        ctx.show_viewport_immediate(
+            converter_id,
+            egui::ViewportBuilder::default()
+                .with_title("Color Converter")
+                .with_inner_size([420.0, 520.0])
+                .with_min_inner_size([360.0, 400.0])
+                .with_resizable(true),
+            |ctx, _class| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        ui.label(egui::RichText::new("Color").size(14.0).strong());
+                        ui.add_space(4.0);
+                        
+                        let mut text = self.converter_text.clone();
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut text)
+                                .hint_text("#3B82F6, rgb(59 130 246), oklch(…), steelblue")
+                                .font(egui::TextStyle::Monospace)
+                                .desired_width(f32::INFINITY),
+                        );
+                        if response.changed() {
+                            self.set_converter_text(text);
+                        }
+                        if let Some(error) = &self.converter_error {
+                            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+                        }
+                        
+                        ui.add_space(8.0);
+                        let color = self.converter_color;
+                        let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 48.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 6.0, egui::Color32::from_rgba_unmultiplied(
+                            color.rgb.0, color.rgb.1, color.rgb.2, color.alpha.unwrap_or(255),
+                        ));
+                        ui.painter().rect_stroke(rect, 6.0, egui::Stroke::new(1.0, self.palette.divider));
+                        
+                        ui.add_space(12.0);
+                        egui::Grid::new("converter_formats").num_columns(3).spacing([12.0, 6.0]).show(ui, |ui| {
+                            for format in Format::ALL {
+                                let mut value = format.format(color);
+                                if format == Format::Name && !notation::nearest_name(color.rgb).1 {
+                                    value = format!("≈ {}", value);
+                                }
+                                
+                                ui.label(egui::RichText::new(format.label()).color(self.palette.muted));
+                                ui.label(egui::RichText::new(&value).family(egui::FontFamily::Monospace));
+                                if ui.small_button("Copy").clicked() {
+                                    let value = value.trim_start_matches("≈ ").to_string();
+                                    self.copy(&value);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                        
+                        ui.add_space(12.0);
+                        ui.separator();
+                        ui.add_space(8.0);
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("Adjust in:");
+                            for space in SliderSpace::ALL {
+                                if ui.radio(self.converter_space == space, space.label()).clicked() {
+                                    self.converter_space = space;
+                                    self.converter_channels = space.decompose(color.rgb);
+                                }
+                            }
+                        });
+                        ui.add_space(4.0);
+                        
+                        let mut changed = false;
+                        let space = self.converter_space;
+                        for ((name, range), value) in space.channels().into_iter().zip(self.converter_channels.iter_mut()) {
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(name).family(egui::FontFamily::Monospace));
+                                changed |= ui.add(egui::Slider::new(value, range)).changed();
+                            });
+                        }
+                        
+                        let mut alpha = color.alpha.unwrap_or(255) as f32 / 255.0;
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("A").family(egui::FontFamily::Monospace));
+                            changed |= ui.add(egui::Slider::new(&mut alpha, 0.0..=1.0)).changed();
+                        });
+                        
+                        if changed {
+                            let alpha = (alpha * 255.0).round() as u8;
+                            self.converter_color = ParsedColor {
+                                rgb: space.compose(self.converter_channels),
+                                alpha: (alpha < 255).then_some(alpha),
+                            };
+                            self.converter_text = Format::Hex.format(self.converter_color);
+                            self.converter_error = None;
+                        }
+                        
+                        ui.add_space(12.0);
+                        ui.horizontal(|ui| {
+                            if ui.button("Copy & add to history").clicked() {
+                                let color = self.converter_color;
+                                let hex = Format::Hex.format(color);
+                                self.copy(&hex);
+                                self.history.add_color(hex, color.rgb);
+                            }
+                            
+                            if let Some((msg, _)) = &self.copy_message {
+                                ui.label(egui::RichText::new(msg).color(self.palette.accent));
+                            }
+                        });
+                    });
+                });
+                
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    self.show_converter_window = false;
+                }
+            },
+        );
+    }
+    
     fn draw_settings_window(&mut self, ctx: &egui::Context) {
         if !self.show_settings_window {
             return;
@@ -386,150 +951,359 @@ impl ConfigApp {
                 .with_resizable(true),
             |ctx, _class| {
                 egui::CentralPanel::default().show(ctx, |ui| {
-                    ui.label(egui::RichText::new("Global Hotkey").size(14.0).strong());
-                    ui.add_space(8.0);
-                    
-                    ui.horizontal(|ui| {
-                        let parts: Vec<&str> = self.config.hotkey.split('+').collect();
-                        let current_key = parts.last().unwrap_or(&"A").trim().to_string();
-                        
-                        let has_super = self.config.hotkey.contains("Super");
-                        let has_shift = self.config.hotkey.contains("Shift");
-                        let has_ctrl = self.config.hotkey.contains("Ctrl");
-                        let has_alt = self.config.hotkey.contains("Alt");
-                        
-                        let mut new_super = has_super;
-                        let mut new_shift = has_shift;
-                        let mut new_ctrl = has_ctrl;
-                        let mut new_alt = has_alt;
-                        let mut new_key = current_key.clone();
-                        
-                        ui.checkbox(&mut new_super, "Super");
-                        ui.checkbox(&mut new_shift, "Shift");
-                        ui.checkbox(&mut new_ctrl, "Ctrl");
-                        ui.checkbox(&mut new_alt, "Alt");
-                        
-                        ui.label("+");
-                        
-                        egui::ComboBox::from_id_salt("key")
-                            .selected_text(&new_key)
-                            .show_ui(ui, |ui| {
-                                for key in 'A'..='Z' {
-                                    let key_str = key.to_string();
-                                    ui.selectable_value(&mut new_key, key_str.clone(), key_str);
-                                }
-                            });
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        ui.label(egui::RichText::new("Global Hotkey").size(14.0).strong());
+                        ui.add_space(8.0);
                         
-                        let mut parts = Vec::new();
-                        if new_super { parts.push("Super"); }
-                        if new_shift { parts.push("Shift"); }
-                        if new_ctrl { parts.push("Ctrl"); }
-                        if new_alt { parts.push("Alt"); }
-                        parts.push(&new_key);
+                        self.record_hotkey(ctx);
                         
-                        self.config.hotkey = parts.join("+");
-                    });
-                    
-                    ui.add_space(6.0);
-                    
-                    let parts: Vec<&str> = self.config.hotkey.split('+').collect();
-                    let modifier_count = parts.len() - 1;
-                    
-                    if modifier_count == 0 {
-                        ui.label(
-                            egui::RichText::new("⚠️ At least one modifier required (Super/Shift/Ctrl/Alt)")
-                                .size(11.0)
-                                .color(egui::Color32::from_rgb(239, 68, 68))
-                        );
-                    } else {
-                        ui.label(
-                            egui::RichText::new(&format!("Current: {}", self.config.hotkey))
-                                .size(12.0)
-                                .color(egui::Color32::from_gray(180))
-                        );
-                    }
-                    
-                    ui.add_space(4.0);
-                    ui.label(
-                        egui::RichText::new("⚠️ Restart daemon after changing")
-                            .size(11.0)
-                            .color(egui::Color32::from_rgb(251, 191, 36))
-                    );
-                    
-                    ui.add_space(12.0);
-                    ui.separator();
-                    ui.add_space(12.0);
-                    
-                    ui.label(egui::RichText::new("Startup Options").size(14.0).strong());
-                    ui.add_space(8.0);
-                    
-                    let mut autostart_enabled = self.autostart.is_enabled();
-                    if ui.checkbox(&mut autostart_enabled, "Launch daemon at startup").changed() {
-                        if autostart_enabled {
-                            if let Err(e) = self.autostart.enable() {
-                                self.save_message = Some((format!("Autostart error: {}", e), std::time::Instant::now()));
+                        ui.horizontal(|ui| {
+                            let button_text = if self.recording_hotkey {
+                                egui::RichText::new("Press a key combination… (Esc to cancel)")
+                                    .color(egui::Color32::from_rgb(251, 191, 36))
                             } else {
-                                self.save_message = Some(("Autostart enabled!".to_string(), std::time::Instant::now()));
+                                egui::RichText::new(self.config.hotkey.to_string())
+                                    .family(egui::FontFamily::Monospace)
+                            };
+                            
+                            let response = ui.add_sized(
+                                [260.0, 32.0],
+                                egui::Button::new(button_text)
+                                    .fill(self.palette.card)
+                                    .rounding(8.0)
+                            );
+                            if response.clicked() {
+                                self.recording_hotkey = !self.recording_hotkey;
+                                // Keep Space/Enter from re-triggering the button while recording
+                                response.surrender_focus();
                             }
-                        } else {
-                            if let Err(e) = self.autostart.disable() {
-                                self.save_message = Some((format!("Autostart error: {}", e), std::time::Instant::now()));
-                            } else {
-                                self.save_message = Some(("Autostart disabled!".to_string(), std::time::Instant::now()));
+                            
+                            let mut with_super = self.config.hotkey.modifiers.contains(Modifiers::SUPER);
+                            if ui.checkbox(&mut with_super, "Super")
+                                .on_hover_text("The Super key can't be detected while recording, toggle it here")
+                                .changed()
+                            {
+                                self.config.hotkey.modifiers.set(Modifiers::SUPER, with_super);
+                                self.hotkey_text = self.config.hotkey.to_string();
+                                self.hotkey_check = None;
                             }
-                        }
-                    }
-                    
-                    ui.add_space(12.0);
-                    ui.separator();
-                    ui.add_space(12.0);
-                    
-                    ui.label(egui::RichText::new("Display Options").size(14.0).strong());
-                    ui.add_space(8.0);
-                    
-                    ui.checkbox(&mut self.config.show_hex, "Show HEX codes");
-                    ui.checkbox(&mut self.config.show_rgb, "Show RGB values");
-                    ui.checkbox(&mut self.config.show_hsl, "Show HSL values");
-                    
-                    ui.add_space(12.0);
-                    ui.separator();
-                    ui.add_space(12.0);
-                    
-                    ui.label("Preview Size");
-                    ui.add(egui::Slider::new(&mut self.config.preview_size, 50..=200).suffix(" px"));
-                    
-                    ui.add_space(16.0);
-                    
-                    ui.horizontal(|ui| {
-                        if ui.add_sized(
-                            [120.0, 36.0],
-                            egui::Button::new(egui::RichText::new("Save Settings").color(egui::Color32::BLACK))
-                                .fill(egui::Color32::from_rgb(34, 197, 94))
-                                .rounding(8.0)
-                        ).clicked() {
-                            if let Err(e) = self.config.validate_hotkey() {
-                                self.save_message = Some((format!("Invalid hotkey: {}", e), std::time::Instant::now()));
-                            } else {
-                                match self.config.save() {
-                                    Ok(_) => {
-                                        self.save_message = Some(("Settings saved!".to_string(), std::time::Instant::now()));
-                                    }
-                                    Err(e) => {
-                                        self.save_message = Some((format!("Error: {}", e), std::time::Instant::now()));
+                        });
+                        
+                        ui.add_space(6.0);
+                        
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("Or type it:").size(12.0).color(self.palette.text));
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.hotkey_text)
+                                    .font(egui::TextStyle::Monospace)
+                                    .desired_width(180.0)
+                            );
+                            if response.changed() {
+                                match Hotkey::parse(&self.hotkey_text) {
+                                    Ok(hotkey) => {
+                                        self.config.hotkey = hotkey;
+                                        self.hotkey_error = None;
+                                        self.hotkey_check = None;
                                     }
+                                    Err(e) => self.hotkey_error = Some(e),
                                 }
                             }
+                            if response.lost_focus() && self.hotkey_error.is_none() {
+                                self.hotkey_text = self.config.hotkey.to_string();
+                            }
+                        });
+                        
+                        ui.add_space(6.0);
+                        
+                        if let Some(error) = &self.hotkey_error {
+                            ui.label(
+                                egui::RichText::new(format!("⚠️ {}", error))
+                                    .size(11.0)
+                                    .color(egui::Color32::from_rgb(239, 68, 68))
+                            );
+                        } else if let Err(e) = self.config.validate_hotkey() {
+                            ui.label(
+                                egui::RichText::new(format!("⚠️ {}", e))
+                                    .size(11.0)
+                                    .color(egui::Color32::from_rgb(239, 68, 68))
+                            );
+                        } else {
+                            ui.label(
+                                egui::RichText::new(format!("Current: {}", self.config.hotkey))
+                                    .size(12.0)
+                                    .color(self.palette.text)
+                            );
                         }
                         
-                        if let Some((msg, _)) = &self.save_message {
-                            ui.add_space(8.0);
-                            let color = if msg.contains("Invalid") || msg.contains("Error") {
-                                egui::Color32::from_rgb(239, 68, 68)
-                            } else {
-                                egui::Color32::from_rgb(34, 197, 94)
+                        ui.add_space(6.0);
+                        
+                        ui.horizontal(|ui| {
+                            if ui.button("Check availability").clicked() {
+                                self.check_hotkey();
+                            }
+                            
+                            match &self.hotkey_check {
+                                Some(HotkeyCheck::Checking) => {
+                                    ui.spinner();
+                                }
+                                Some(HotkeyCheck::Free(msg)) => {
+                                    ui.label(egui::RichText::new(format!("✅ {}", msg)).size(11.0).color(egui::Color32::from_rgb(34, 197, 94)));
+                                }
+                                Some(HotkeyCheck::Taken(e)) => {
+                                    ui.label(egui::RichText::new(format!("❌ {}", e)).size(11.0).color(egui::Color32::from_rgb(239, 68, 68)));
+                                }
+                                Some(HotkeyCheck::Unknown(msg)) => {
+                                    ui.label(egui::RichText::new(format!("ℹ {}", msg)).size(11.0).color(self.palette.muted));
+                                }
+                                None => {}
+                            }
+                        });
+                        
+                        if !self.hotkey_suggestions.is_empty() {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(egui::RichText::new("Try:").size(11.0).color(self.palette.text));
+                                let mut picked = None;
+                                for hotkey in &self.hotkey_suggestions {
+                                    if ui.small_button(hotkey.to_string()).clicked() {
+                                        picked = Some(*hotkey);
+                                    }
+                                }
+                                if let Some(hotkey) = picked {
+                                    self.config.hotkey = hotkey;
+                                    self.hotkey_text = hotkey.to_string();
+                                    self.hotkey_error = None;
+                                    self.hotkey_check = None;
+                                    self.hotkey_suggestions.clear();
+                                }
+                            });
+                        }
+                        
+                        ui.add_space(4.0);
+                        ui.label(
+                            egui::RichText::new("Saving applies the hotkey to the running daemon")
+                                .size(11.0)
+                                .color(self.palette.muted)
+                        );
+                        
+                        ui.add_space(12.0);
+                        ui.separator();
+                        ui.add_space(12.0);
+                        
+                        ui.label(egui::RichText::new("Startup Options").size(14.0).strong());
+                        ui.add_space(8.0);
+                        
+                        let mut autostart_enabled = self.autostart.is_enabled();
+                        if ui.checkbox(&mut autostart_enabled, "Launch daemon at startup").changed() {
+                            self.set_autostart(autostart_enabled);
+                        }
+                        
+                        ui.horizontal(|ui| {
+                            ui.add_space(24.0);
+                            ui.label(egui::RichText::new("Using:").size(12.0).color(self.palette.text));
+                            
+                            let previous = self.autostart_backend;
+                            ui.radio_value(&mut self.autostart_backend, AutostartBackend::DesktopFile, "Desktop entry")
+                                .on_hover_text("~/.config/autostart, started by GNOME, KDE, XFCE and other XDG sessions");
+                            ui.add_enabled_ui(self.systemd_available, |ui| {
+                                ui.radio_value(&mut self.autostart_backend, AutostartBackend::Systemd, "systemd user service")
+                                    .on_hover_text("Restarted on failure, works with window managers that ignore XDG autostart")
+                                    .on_disabled_hover_text("No systemd user manager is running");
+                            });
+                            
+                            // Switching backends while enabled moves the existing entry over
+                            if self.autostart_backend != previous && autostart_enabled {
+                                self.set_autostart(true);
+                            }
+                        });
+                        
+                        if let Some(state) = &self.service_state {
+                            let color = match state.as_str() {
+                                "active" => egui::Color32::from_rgb(34, 197, 94),
+                                "failed" => egui::Color32::from_rgb(239, 68, 68),
+                                _ => self.palette.muted,
                             };
-                            ui.label(egui::RichText::new(msg).color(color));
+                            ui.horizontal(|ui| {
+                                ui.add_space(24.0);
+                                ui.label(egui::RichText::new(format!("Service: {}", state)).size(11.0).color(color));
+                            });
                         }
+                        
+                        ui.add_space(8.0);
+                        ui.label(egui::RichText::new("Daemon arguments").size(12.0).color(self.palette.text));
+                        let args_changed = ui.add(
+                            egui::TextEdit::singleline(&mut self.daemon_args_text)
+                                .hint_text("appended to `yoinkctl daemon`")
+                                .desired_width(300.0)
+                        ).changed();
+                        
+                        ui.label(egui::RichText::new("Environment (one KEY=VALUE per line)").size(12.0).color(self.palette.text));
+                        let env_changed = ui.add(
+                            egui::TextEdit::multiline(&mut self.daemon_env_text)
+                                .hint_text("WINIT_UNIX_BACKEND=x11")
+                                .desired_rows(2)
+                                .desired_width(300.0)
+                        ).changed();
+                        
+                        if args_changed || env_changed {
+                            self.parse_daemon_launch();
+                        }
+                        
+                        if let Some(e) = &self.daemon_launch_error {
+                            ui.label(egui::RichText::new(format!("❌ {}", e)).size(11.0).color(egui::Color32::from_rgb(239, 68, 68)));
+                        } else {
+                            ui.label(
+                                egui::RichText::new("Used by the autostart entry, applied when saving")
+                                    .size(11.0)
+                                    .color(self.palette.muted)
+                            );
+                        }
+                        
+                        ui.add_space(8.0);
+                        ui.checkbox(&mut self.config.persistent_picker, "Keep picker pre-loaded in the daemon")
+                            .on_hover_text("Opens instantly on hotkey instead of starting a new process. Applies after restarting the daemon");
+                        ui.checkbox(&mut self.config.notify_on_pick, "Show a notification after each pick")
+                            .on_hover_text("Confirms what was copied, with buttons to copy as RGB or open the history");
+                        if cfg!(target_os = "linux") {
+                            ui.checkbox(&mut self.config.copy_to_primary, "Also copy to the primary selection")
+                                .on_hover_text("Paste picked colors with a middle click as well");
+                        }
+                        
+                        ui.add_space(12.0);
+                        ui.separator();
+                        ui.add_space(12.0);
+                        
+                        ui.label(egui::RichText::new("Display Options").size(14.0).strong());
+                        ui.add_space(8.0);
+                        
+                        ui.checkbox(&mut self.config.show_hex, "Show HEX codes");
+                        ui.checkbox(&mut self.config.show_rgb, "Show RGB values");
+                        ui.checkbox(&mut self.config.show_hsl, "Show HSL values");
+                        ui.checkbox(&mut self.config.show_coordinates, "Show pixel coordinates");
+                        ui.checkbox(&mut self.config.show_grid, "Show grid in magnifier");
+                        
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            ui.label("Overlay:");
+                            ui.radio_value(&mut self.config.overlay_mode, OverlayMode::Freeze, "Freeze")
+                                .on_hover_text("Show the screenshot taken when the picker opens, exactly what gets sampled");
+                            ui.radio_value(&mut self.config.overlay_mode, OverlayMode::Live, "Live")
+                                .on_hover_text("Show the live desktop and keep re-capturing around the cursor. Not available with the Wayland portal");
+                        });
+                        
+                        ui.add_space(12.0);
+                        ui.separator();
+                        ui.add_space(12.0);
+                        
+                        ui.label("Preview Size");
+                        ui.add(egui::Slider::new(&mut self.config.preview_size, 50..=200).suffix(" px"));
+                        
+                        ui.add_space(12.0);
+                        ui.separator();
+                        ui.add_space(12.0);
+                        
+                        ui.label(egui::RichText::new("Appearance").size(14.0).strong());
+                        ui.add_space(8.0);
+                        
+                        let theme = &mut self.config.theme;
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("Theme:").size(12.0).color(self.palette.text));
+                            for mode in ThemeMode::ALL {
+                                ui.radio_value(&mut theme.mode, mode, mode.label());
+                            }
+                        });
+                        
+                        let color_button = |ui: &mut egui::Ui, label: &str, (r, g, b): &mut (u8, u8, u8)| {
+                            ui.horizontal(|ui| {
+                                let mut rgb = [*r, *g, *b];
+                                ui.color_edit_button_srgb(&mut rgb);
+                                [*r, *g, *b] = rgb;
+                                ui.label(label);
+                            });
+                        };
+                        color_button(ui, "Accent", &mut theme.accent);
+                        color_button(ui, "Crosshair", &mut theme.crosshair_color);
+                        
+                        ui.label("Crosshair size");
+                        ui.add(egui::Slider::new(&mut theme.crosshair_size, 8.0..=60.0).suffix(" pt"));
+                        ui.label("Text size");
+                        ui.add(egui::Slider::new(&mut theme.font_scale, 0.75..=2.0).suffix("×"));
+                        ui.checkbox(&mut theme.reduced_motion, "Reduce motion")
+                            .on_hover_text("The magnifier follows the cursor without smoothing, and the app doesn't animate");
+                        
+                        ui.add_space(12.0);
+                        ui.separator();
+                        ui.add_space(12.0);
+                        
+                        ui.label(egui::RichText::new("Color Management").size(14.0).strong());
+                        ui.add_space(8.0);
+                        
+                        ui.label(egui::RichText::new("Monitor ICC profile (empty: use the one the desktop publishes)").size(12.0).color(self.palette.text));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.icc_profile_text)
+                                .hint_text("/usr/share/color/icc/monitor.icc")
+                                .desired_width(f32::INFINITY)
+                        );
+                        
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("Convert to:").size(12.0).color(self.palette.text));
+                            ui.radio_value(&mut self.config.managed_space, ManagedSpace::Srgb, "sRGB");
+                            ui.radio_value(&mut self.config.managed_space, ManagedSpace::DisplayP3, "Display P3");
+                        });
+                        ui.label(
+                            egui::RichText::new("Per-monitor profiles go in \"monitor_icc_profiles\" in config.json")
+                                .size(11.0)
+                                .color(self.palette.muted)
+                        );
+                        
+                        ui.add_space(16.0);
+                        
+                        ui.horizontal(|ui| {
+                            if ui.add_sized(
+                                [120.0, 36.0],
+                                egui::Button::new(egui::RichText::new("Save Settings").color(egui::Color32::BLACK))
+                                    .fill(egui::Color32::from_rgb(34, 197, 94))
+                                    .rounding(8.0)
+                            ).clicked() {
+                                if let Some(e) = &self.hotkey_error {
+                                    self.save_message = Some((format!("Invalid hotkey: {}", e), std::time::Instant::now()));
+                                } else if let Err(e) = self.config.validate_hotkey() {
+                                    self.save_message = Some((format!("Invalid hotkey: {}", e), std::time::Instant::now()));
+                                } else if let Some(e) = &self.daemon_launch_error {
+                                    self.save_message = Some((format!("Invalid daemon launch settings: {}", e), std::time::Instant::now()));
+                                } else if let Err(e) = self.apply_icc_profile() {
+                                    self.save_message = Some((format!("Invalid ICC profile: {}", e), std::time::Instant::now()));
+                                } else if let Err(e) = self.rewrite_autostart() {
+                                    self.save_message = Some((format!("Error: {}", e), std::time::Instant::now()));
+                                } else {
+                                    match self.config.save() {
+                                        Ok(_) if self.daemon_running => {
+                                            // Let the running daemon pick up a changed hotkey right away
+                                            let message = match ipc::send(Request::Reload) {
+                                                Ok(_) => "Settings saved and applied!".to_string(),
+                                                Err(e) => format!("Saved, but daemon reload failed: {}", e),
+                                            };
+                                            self.save_message = Some((message, std::time::Instant::now()));
+                                        }
+                                        Ok(_) => {
+                                            self.save_message = Some(("Settings saved!".to_string(), std::time::Instant::now()));
+                                        }
+                                        Err(e) => {
+                                            self.save_message = Some((format!("Error: {}", e), std::time::Instant::now()));
+                                        }
+                                    }
+                                }
+                            }
+                            
+                            if let Some((msg, _)) = &self.save_message {
+                                ui.add_space(8.0);
+                                let color = if msg.contains("Invalid") || msg.contains("Error") || msg.contains("failed") {
+                                    egui::Color32::from_rgb(239, 68, 68)
+                                } else {
+                                    egui::Color32::from_rgb(34, 197, 94)
+                                };
+                                ui.label(egui::RichText::new(msg).color(color));
+                            }
+                        });
                     });
                 });
                 
@@ -543,7 +1317,14 @@ impl ConfigApp {
 
 impl eframe::App for ConfigApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if self.applied_theme.as_ref() != Some(&self.config.theme) {
+            self.config.theme.apply(ctx);
+            self.applied_theme = Some(self.config.theme.clone());
+        }
+        self.palette = Palette::new(&self.config.theme, self.config.theme.is_dark(ctx));
+        
         self.reload_history_if_needed();
+        self.receive_hotkey_check();
         self.clear_expired_messages();
         ctx.request_repaint();
         
@@ -551,8 +1332,12 @@ impl eframe::App for ConfigApp {
             self.draw_settings_window(ctx);
         }
         
+        if self.show_converter_window {
+            self.draw_converter_window(ctx);
+        }
+        
         egui::CentralPanel::default()
-            .frame(egui::Frame::none().fill(egui::Color32::from_rgb(18, 18, 20)))
+            .frame(egui::Frame::none().fill(self.palette.background))
             .show(ctx, |ui| {
                 ui.add_space(30.0);
                 self.render_header(ui);
@@ -566,26 +1351,61 @@ impl eframe::App for ConfigApp {
     }
 }
 
+/// Whether `hotkey` can be grabbed, with free alternatives when it can't
+fn probe_hotkey(hotkey: Hotkey) -> HotkeyProbe {
+    // The running daemon holds its own grab, which would look like a conflict
+    let held_by_daemon = ipc::send(Request::Status)
+        .is_ok_and(|response| response.hotkey == Some(hotkey.to_string()));
+    if held_by_daemon {
+        return (HotkeyCheck::Free(format!("{} is in use by the yoinkctl daemon", hotkey)), Vec::new());
+    }
+    
+    // The compositor binds shortcuts there, and without XWayland there's no
+    // X server to ask
+    if diagnostics::session_type() == "wayland" && env::var_os("DISPLAY").is_none() {
+        return (HotkeyCheck::Unknown("Can't check on Wayland, the compositor decides when binding it".to_string()), Vec::new());
+    }
+    
+    match diagnostics::probe_hotkey(&hotkey) {
+        Ok(()) => (HotkeyCheck::Free(format!("{} is free", hotkey)), Vec::new()),
+        Err(e) => (HotkeyCheck::Taken(e), diagnostics::suggest_alternatives(&hotkey, 3)),
+    }
+}
+
 fn is_daemon_running() -> bool {
-    #[cfg(target_os = "linux")]
-    {
-        let output = Command::new("pgrep")
-            .arg("-f")
-            .arg("yoinkctl daemon")
-            .output();
-        
-        if let Ok(output) = output {
-            !output.stdout.is_empty()
-        } else {
-            false
+    ipc::send(Request::Status).is_ok()
+}
+
+/// Checks on the daemon once a second from a background thread, until the
+/// app drops the returned flag
+fn poll_daemon(ctx: egui::Context) -> Arc<AtomicBool> {
+    let running = Arc::new(AtomicBool::new(is_daemon_running()));
+    let weak = Arc::downgrade(&running);
+    
+    std::thread::spawn(move || {
+        while let Some(running) = weak.upgrade() {
+            let now_running = is_daemon_running();
+            if running.swap(now_running, Ordering::Relaxed) != now_running {
+                ctx.request_repaint();
+            }
+            drop(running);
+            std::thread::sleep(std::time::Duration::from_secs(1));
         }
-    }
+    });
     
-    #[cfg(not(target_os = "linux"))]
-    false
+    running
 }
 
-fn start_daemon() {
+fn start_daemon(autostart: &Autostart) -> Result<(), String> {
+    if is_daemon_running() {
+        return Ok(());
+    }
+    
+    // A second daemon beside the unit's would leave systemd tracking the wrong one
+    if autostart.active_backend() == Some(AutostartBackend::Systemd) {
+        return autostart::start_service();
+    }
+    
     let exe_path = env::current_exe()
         .ok()
         .and_then(|p| p.to_str().map(|s| s.to_string()))
@@ -610,15 +1430,17 @@ fn start_daemon() {
             .spawn()
             .ok();
     }
+    
+    Ok(())
 }
 
-fn stop_daemon() {
-    #[cfg(target_os = "linux")]
-    {
-        Command::new("pkill")
-            .arg("-f")
-            .arg("yoinkctl daemon")
-            .output()
-            .ok();
+fn stop_daemon(autostart: &Autostart) -> Result<(), String> {
+    // A daemon started by hand next to an enabled unit still stops over IPC
+    let unit_running = autostart.active_backend() == Some(AutostartBackend::Systemd)
+        && autostart::systemd_status().as_deref() == Some("active");
+    if unit_running {
+        return autostart::stop_service();
     }
+    
+    ipc::send(Request::Stop).map(|_| ())
 }
\ No newline at end of file
@@ -0,0 +1,158 @@
+use crate::color::{self, Oklch};
+
+/// Classic color-wheel schemes, rotating the hue in HSL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    Complementary,
+    Analogous,
+    Triadic,
+    SplitComplementary,
+    Tetradic,
+}
+
+impl Scheme {
+    pub const ALL: [Scheme; 5] = [
+        Scheme::Complementary,
+        Scheme::Analogous,
+        Scheme::Triadic,
+        Scheme::SplitComplementary,
+        Scheme::Tetradic,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Scheme::Complementary => "Complementary",
+            Scheme::Analogous => "Analogous",
+            Scheme::Triadic => "Triadic",
+            Scheme::SplitComplementary => "Split complementary",
+            Scheme::Tetradic => "Tetradic",
+        }
+    }
+
+    /// Hue offsets from the base color, which is always the first
+    fn offsets(self) -> &'static [f32] {
+        match self {
+            Scheme::Complementary => &[0.0, 180.0],
+            Scheme::Analogous => &[0.0, -30.0, 30.0],
+            Scheme::Triadic => &[0.0, 120.0, 240.0],
+            Scheme::SplitComplementary => &[0.0, 150.0, 210.0],
+            Scheme::Tetradic => &[0.0, 90.0, 180.0, 270.0],
+        }
+    }
+
+    pub fn colors(self, rgb: (u8, u8, u8)) -> Vec<(u8, u8, u8)> {
+        let (h, s, l) = color::rgb_to_hsl(rgb.0, rgb.1, rgb.2);
+        self.offsets()
+            .iter()
+            .map(|&offset| if offset == 0.0 { rgb } else { color::hsl_to_rgb(h + offset, s, l) })
+            .collect()
+    }
+}
+
+/// Tailwind-style steps, each with its OKLCH lightness and a chroma factor
+/// that tapers towards the near-white and near-black ends
+const SHADE_STEPS: [(u16, f32, f32); 11] = [
+    (50, 0.975, 0.12),
+    (100, 0.935, 0.25),
+    (200, 0.88, 0.45),
+    (300, 0.81, 0.7),
+    (400, 0.72, 0.9),
+    (500, 0.64, 1.0),
+    (600, 0.56, 1.0),
+    (700, 0.48, 0.9),
+    (800, 0.40, 0.75),
+    (900, 0.33, 0.6),
+    (950, 0.24, 0.45),
+];
+
+/// A 50–950 scale in OKLCH keeping the base hue. The step closest in
+/// lightness to the base color is the base color itself.
+pub fn shades(rgb: (u8, u8, u8)) -> Vec<(u16, (u8, u8, u8))> {
+    let base = Oklch::from_rgb(rgb);
+    let closest = SHADE_STEPS
+        .iter()
+        .min_by(|a, b| (a.1 - base.l).abs().total_cmp(&(b.1 - base.l).abs()))
+        .map(|step| step.0);
+
+    SHADE_STEPS
+        .iter()
+        .map(|&(step, l, chroma)| {
+            if Some(step) == closest {
+                (step, rgb)
+            } else {
+                (step, Oklch { l, c: base.c * chroma, h: base.h }.to_rgb())
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hue distance from `from` to `to`, going round the wheel forwards
+    fn hue_offset(from: (u8, u8, u8), to: (u8, u8, u8)) -> f32 {
+        let hue = |(r, g, b): (u8, u8, u8)| color::rgb_to_hsl(r, g, b).0;
+        (hue(to) - hue(from)).rem_euclid(360.0)
+    }
+
+    #[test]
+    fn complementary_is_opposite_on_the_wheel() {
+        assert_eq!(Scheme::Complementary.colors((255, 0, 0)), [(255, 0, 0), (0, 255, 255)]);
+
+        let base = (200, 80, 40);
+        let colors = Scheme::Complementary.colors(base);
+        assert_eq!(colors[0], base);
+        assert!((hue_offset(base, colors[1]) - 180.0).abs() < 1.0, "{:?}", colors);
+    }
+
+    #[test]
+    fn schemes_rotate_by_their_offsets() {
+        let base = (40, 160, 90);
+        for scheme in Scheme::ALL {
+            let colors = scheme.colors(base);
+            assert_eq!(colors.len(), scheme.offsets().len());
+            assert_eq!(colors[0], base, "{}", scheme.label());
+
+            for (color, offset) in colors.iter().zip(scheme.offsets()).skip(1) {
+                let expected = offset.rem_euclid(360.0);
+                assert!((hue_offset(base, *color) - expected).abs() < 1.5, "{} {:?}", scheme.label(), color);
+            }
+        }
+    }
+
+    #[test]
+    fn shade_500_is_the_input() {
+        // Tailwind's blue-500, OKLCH lightness 0.62
+        let base = (0x3b, 0x82, 0xf6);
+        let shades = shades(base);
+
+        let steps: Vec<u16> = shades.iter().map(|&(step, _)| step).collect();
+        assert_eq!(steps, [50, 100, 200, 300, 400, 500, 600, 700, 800, 900, 950]);
+        assert_eq!(shades[5], (500, base));
+    }
+
+    #[test]
+    fn shades_get_darker_from_50_to_950() {
+        for base in [(0x3b, 0x82, 0xf6), (220, 38, 38), (250, 204, 21), (30, 30, 30)] {
+            let lightness: Vec<f32> = shades(base).iter().map(|&(_, rgb)| Oklch::from_rgb(rgb).l).collect();
+            assert!(lightness.windows(2).all(|pair| pair[0] > pair[1]), "{:?}: {:?}", base, lightness);
+
+            // Near-black input is its own 950
+            let base_l = Oklch::from_rgb(base).l;
+            assert!(lightness[0] > base_l && lightness[10] <= base_l, "{:?}", base);
+        }
+    }
+
+    #[test]
+    fn shades_keep_the_hue() {
+        let base = (220, 38, 38);
+        let hue = Oklch::from_rgb(base).h;
+        // The ends lose most of their chroma and round to nearly gray
+        for &(step, rgb) in &shades(base)[2..10] {
+            let shade = Oklch::from_rgb(rgb).h;
+            let difference = (shade - hue + 180.0).rem_euclid(360.0) - 180.0;
+            assert!(difference.abs() < 3.0, "{} is {} degrees off", step, difference);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use crate::palette::PaletteColor;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColorEntry {
     pub hex: String,
@@ -8,10 +10,19 @@ pub struct ColorEntry {
     pub timestamp: i64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedPalette {
+    pub name: String,
+    pub colors: Vec<PaletteColor>,
+    pub timestamp: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColorHistory {
     pub entries: Vec<ColorEntry>,
     max_entries: usize,
+    #[serde(default)]
+    pub palettes: Vec<NamedPalette>,
 }
 
 impl Default for ColorHistory {
@@ -19,6 +30,7 @@ impl Default for ColorHistory {
         Self {
             entries: Vec::new(),
             max_entries: 50,
+            palettes: Vec::new(),
         }
     }
 }
@@ -90,6 +102,24 @@ impl ColorHistory {
         }
     }
     
+    /// Saves a palette at the top, replacing any older one with the same name
+    pub fn add_palette(&mut self, name: String, colors: Vec<PaletteColor>) -> Result<(), String> {
+        self.palettes.retain(|palette| palette.name != name);
+        self.palettes.insert(0, NamedPalette {
+            name,
+            colors,
+            timestamp: chrono::Utc::now().timestamp(),
+        });
+        self.palettes.truncate(self.max_entries);
+        
+        self.save()
+    }
+    
+    pub fn remove_palette(&mut self, name: &str) {
+        self.palettes.retain(|palette| palette.name != name);
+        self.save().ok();
+    }
+    
     pub fn clear(&mut self) {
         self.entries.clear();
         self.save().ok();
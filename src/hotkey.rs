@@ -0,0 +1,406 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use eframe::egui;
+
+/// A global hotkey: a set of modifiers plus exactly one key.
+///
+/// Stored in the config as a human readable string such as `Super+Shift+A`,
+/// `Ctrl+Alt+F5` or `Super+PrintScreen`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    pub modifiers: Modifiers,
+    pub code: Code,
+}
+
+impl Hotkey {
+    pub fn new(modifiers: Modifiers, code: Code) -> Self {
+        Self { modifiers, code }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let tokens: Vec<&str> = text.split('+').map(|t| t.trim()).collect();
+
+        if tokens.iter().any(|t| t.is_empty()) {
+            return Err(format!("Invalid hotkey '{}': empty key name", text));
+        }
+
+        let mut modifiers = Modifiers::empty();
+        let mut code = None;
+
+        for token in tokens {
+            if let Some(modifier) = parse_modifier(token) {
+                if code.is_some() {
+                    return Err(format!("Invalid hotkey '{}': modifiers must come before the key", text));
+                }
+                modifiers |= modifier;
+                continue;
+            }
+
+            if code.is_some() {
+                return Err(format!("Invalid hotkey '{}': only one non-modifier key is allowed", text));
+            }
+            code = Some(parse_key(token)?);
+        }
+
+        let code = code.ok_or_else(|| format!("Invalid hotkey '{}': missing a key after the modifiers", text))?;
+
+        Ok(Self { modifiers, code })
+    }
+
+    /// Validates that the hotkey has at least one modifier
+    pub fn validate(&self) -> Result<(), String> {
+        if self.modifiers.is_empty() {
+            Err("Hotkey must have at least one modifier (Super, Shift, Ctrl, or Alt)".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn to_global(self) -> HotKey {
+        HotKey::new(Some(self.modifiers), self.code)
+    }
+
+    /// Builds a hotkey from a key press seen by egui.
+    ///
+    /// egui doesn't report the Super key on Linux, so it has to be passed in
+    /// separately by the caller.
+    pub fn from_egui(key: egui::Key, modifiers: egui::Modifiers, with_super: bool) -> Option<Self> {
+        let code = code_from_egui_key(key)?;
+
+        let mut mods = Modifiers::empty();
+        if with_super {
+            mods |= Modifiers::SUPER;
+        }
+        if modifiers.shift {
+            mods |= Modifiers::SHIFT;
+        }
+        if modifiers.ctrl {
+            mods |= Modifiers::CONTROL;
+        }
+        if modifiers.alt {
+            mods |= Modifiers::ALT;
+        }
+
+        Some(Self::new(mods, code))
+    }
+}
+
+impl Default for Hotkey {
+    fn default() -> Self {
+        Self::new(Modifiers::SUPER | Modifiers::SHIFT, Code::KeyA)
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::with_capacity(5);
+
+        if self.modifiers.contains(Modifiers::SUPER) {
+            parts.push("Super".to_string());
+        }
+        if self.modifiers.contains(Modifiers::SHIFT) {
+            parts.push("Shift".to_string());
+        }
+        if self.modifiers.contains(Modifiers::CONTROL) {
+            parts.push("Ctrl".to_string());
+        }
+        if self.modifiers.contains(Modifiers::ALT) {
+            parts.push("Alt".to_string());
+        }
+        parts.push(key_name(self.code));
+
+        write!(f, "{}", parts.join("+"))
+    }
+}
+
+impl FromStr for Hotkey {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
+
+impl Serialize for Hotkey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Hotkey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Self::parse(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+fn parse_modifier(token: &str) -> Option<Modifiers> {
+    match token.to_uppercase().as_str() {
+        "SUPER" | "META" | "WIN" | "CMD" => Some(Modifiers::SUPER),
+        "SHIFT" => Some(Modifiers::SHIFT),
+        "CTRL" | "CONTROL" => Some(Modifiers::CONTROL),
+        "ALT" | "OPTION" => Some(Modifiers::ALT),
+        _ => None,
+    }
+}
+
+/// Parses a single key name into a [`Code`].
+///
+/// Accepts the W3C code names (`KeyA`, `Digit1`, `F13`, `PrintScreen`,
+/// `NumpadAdd`, ...) as well as the short forms shown in the UI (`A`, `1`,
+/// `Print`, `-`, ...).
+pub fn parse_key(name: &str) -> Result<Code, String> {
+    let upper = name.to_uppercase();
+
+    let code = Code::from_str(name)
+        .or_else(|_| Code::from_str(&upper))
+        .ok()
+        .or_else(|| key_alias(&upper));
+
+    match code {
+        Some(code) if is_bindable(code) => Ok(code),
+        Some(_) => Err(format!("'{}' can't be used as the hotkey's main key", name)),
+        None => Err(format!("Unknown key '{}' (try names like A, 5, F11, Print, Minus or Numpad0)", name)),
+    }
+}
+
+fn key_alias(upper: &str) -> Option<Code> {
+    let mut chars = upper.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphabetic() {
+            return Code::from_str(&format!("Key{}", c)).ok();
+        }
+        if c.is_ascii_digit() {
+            return Code::from_str(&format!("Digit{}", c)).ok();
+        }
+    }
+
+    if let Some(digit) = upper.strip_prefix("NUM").or_else(|| upper.strip_prefix("KP")) {
+        if digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()) {
+            return Code::from_str(&format!("Numpad{}", digit)).ok();
+        }
+    }
+
+    let code = match upper {
+        "`" | "BACKQUOTE" | "GRAVE" => Code::Backquote,
+        "-" | "MINUS" => Code::Minus,
+        "=" | "EQUAL" | "EQUALS" => Code::Equal,
+        "[" | "BRACKETLEFT" => Code::BracketLeft,
+        "]" | "BRACKETRIGHT" => Code::BracketRight,
+        "\\" | "BACKSLASH" => Code::Backslash,
+        ";" | "SEMICOLON" => Code::Semicolon,
+        "'" | "QUOTE" => Code::Quote,
+        "," | "COMMA" => Code::Comma,
+        "." | "PERIOD" => Code::Period,
+        "/" | "SLASH" => Code::Slash,
+        "PRINT" | "PRTSC" | "PRINTSCREEN" => Code::PrintScreen,
+        "ESC" | "ESCAPE" => Code::Escape,
+        "RETURN" | "ENTER" => Code::Enter,
+        "SPACE" => Code::Space,
+        "TAB" => Code::Tab,
+        "BACKSPACE" => Code::Backspace,
+        "INS" | "INSERT" => Code::Insert,
+        "DEL" | "DELETE" => Code::Delete,
+        "HOME" => Code::Home,
+        "END" => Code::End,
+        "PGUP" | "PAGEUP" => Code::PageUp,
+        "PGDN" | "PAGEDOWN" => Code::PageDown,
+        "UP" | "ARROWUP" => Code::ArrowUp,
+        "DOWN" | "ARROWDOWN" => Code::ArrowDown,
+        "LEFT" | "ARROWLEFT" => Code::ArrowLeft,
+        "RIGHT" | "ARROWRIGHT" => Code::ArrowRight,
+        "PAUSE" => Code::Pause,
+        "SCROLLLOCK" => Code::ScrollLock,
+        "NUMLOCK" => Code::NumLock,
+        "CAPSLOCK" => Code::CapsLock,
+        "NUMPADADD" | "NUMPLUS" => Code::NumpadAdd,
+        "NUMPADSUBTRACT" | "NUMMINUS" => Code::NumpadSubtract,
+        "NUMPADMULTIPLY" => Code::NumpadMultiply,
+        "NUMPADDIVIDE" => Code::NumpadDivide,
+        "NUMPADDECIMAL" => Code::NumpadDecimal,
+        "NUMPADENTER" => Code::NumpadEnter,
+        "NUMPADEQUAL" => Code::NumpadEqual,
+        _ => return None,
+    };
+
+    Some(code)
+}
+
+// Modifier keys and placeholders can't be grabbed as the main key
+fn is_bindable(code: Code) -> bool {
+    !matches!(
+        code,
+        Code::Unidentified
+            | Code::Fn
+            | Code::FnLock
+            | Code::ShiftLeft
+            | Code::ShiftRight
+            | Code::ControlLeft
+            | Code::ControlRight
+            | Code::AltLeft
+            | Code::AltRight
+            | Code::MetaLeft
+            | Code::MetaRight
+    )
+}
+
+/// Short display name for a key, the inverse of [`parse_key`].
+pub fn key_name(code: Code) -> String {
+    let name = code.to_string();
+
+    if let Some(letter) = name.strip_prefix("Key") {
+        return letter.to_string();
+    }
+    if let Some(digit) = name.strip_prefix("Digit") {
+        return digit.to_string();
+    }
+
+    name
+}
+
+fn code_from_egui_key(key: egui::Key) -> Option<Code> {
+    use egui::Key;
+
+    let code = match key {
+        Key::ArrowDown => Code::ArrowDown,
+        Key::ArrowLeft => Code::ArrowLeft,
+        Key::ArrowRight => Code::ArrowRight,
+        Key::ArrowUp => Code::ArrowUp,
+        Key::Escape => Code::Escape,
+        Key::Tab => Code::Tab,
+        Key::Backspace => Code::Backspace,
+        Key::Enter => Code::Enter,
+        Key::Space => Code::Space,
+        Key::Insert => Code::Insert,
+        Key::Delete => Code::Delete,
+        Key::Home => Code::Home,
+        Key::End => Code::End,
+        Key::PageUp => Code::PageUp,
+        Key::PageDown => Code::PageDown,
+        Key::Copy => Code::Copy,
+        Key::Cut => Code::Cut,
+        Key::Paste => Code::Paste,
+        Key::Colon | Key::Semicolon => Code::Semicolon,
+        Key::Comma => Code::Comma,
+        Key::Backslash | Key::Pipe => Code::Backslash,
+        Key::Slash | Key::Questionmark => Code::Slash,
+        Key::OpenBracket => Code::BracketLeft,
+        Key::CloseBracket => Code::BracketRight,
+        Key::Backtick => Code::Backquote,
+        Key::Minus => Code::Minus,
+        Key::Period => Code::Period,
+        // `+` is Shift+= on most layouts, the numpad key would never fire
+        Key::Plus => Code::Equal,
+        Key::Equals => Code::Equal,
+        Key::Quote => Code::Quote,
+        Key::Num0 => Code::Digit0,
+        Key::Num1 => Code::Digit1,
+        Key::Num2 => Code::Digit2,
+        Key::Num3 => Code::Digit3,
+        Key::Num4 => Code::Digit4,
+        Key::Num5 => Code::Digit5,
+        Key::Num6 => Code::Digit6,
+        Key::Num7 => Code::Digit7,
+        Key::Num8 => Code::Digit8,
+        Key::Num9 => Code::Digit9,
+        Key::F1 => Code::F1,
+        Key::F2 => Code::F2,
+        Key::F3 => Code::F3,
+        Key::F4 => Code::F4,
+        Key::F5 => Code::F5,
+        Key::F6 => Code::F6,
+        Key::F7 => Code::F7,
+        Key::F8 => Code::F8,
+        Key::F9 => Code::F9,
+        Key::F10 => Code::F10,
+        Key::F11 => Code::F11,
+        Key::F12 => Code::F12,
+        Key::F13 => Code::F13,
+        Key::F14 => Code::F14,
+        Key::F15 => Code::F15,
+        Key::F16 => Code::F16,
+        Key::F17 => Code::F17,
+        Key::F18 => Code::F18,
+        Key::F19 => Code::F19,
+        Key::F20 => Code::F20,
+        Key::F21 => Code::F21,
+        Key::F22 => Code::F22,
+        Key::F23 => Code::F23,
+        Key::F24 => Code::F24,
+        // Letters share their name with the W3C code minus the "Key" prefix
+        letter => return Code::from_str(&format!("Key{}", letter.name())).ok(),
+    };
+
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hotkey(text: &str) -> Hotkey {
+        Hotkey::parse(text).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    #[test]
+    fn displays_modifiers_in_canonical_order() {
+        let parsed = hotkey("Alt+Shift+Ctrl+Super+A");
+        assert_eq!(parsed.modifiers, Modifiers::SUPER | Modifiers::SHIFT | Modifiers::CONTROL | Modifiers::ALT);
+        assert_eq!(parsed.to_string(), "Super+Shift+Ctrl+Alt+A");
+        assert_eq!(hotkey("Shift+Super+A"), hotkey("Super+Shift+A"));
+    }
+
+    #[test]
+    fn ignores_case_and_spaces() {
+        assert_eq!(hotkey("ctrl + alt + f5").to_string(), "Ctrl+Alt+F5");
+        assert_eq!(hotkey("SUPER+shift+a"), Hotkey::default());
+        assert_eq!(hotkey("Ctrl+printscreen").code, Code::PrintScreen);
+    }
+
+    #[test]
+    fn accepts_aliases() {
+        assert_eq!(hotkey("Win+PrtSc"), hotkey("Super+PrintScreen"));
+        assert_eq!(hotkey("Cmd+Option+-"), hotkey("Super+Alt+Minus"));
+        assert_eq!(hotkey("Meta+Control+Esc"), hotkey("Super+Ctrl+Escape"));
+        assert_eq!(hotkey("Ctrl+Num5").code, Code::Numpad5);
+        assert_eq!(hotkey("Ctrl+KP0").code, Code::Numpad0);
+        assert_eq!(hotkey("Ctrl+7").code, Code::Digit7);
+        assert_eq!(hotkey("Ctrl+KeyQ").code, Code::KeyQ);
+    }
+
+    #[test]
+    fn display_parses_back() {
+        for text in ["Super+Shift+A", "Ctrl+Alt+F24", "Super+PrintScreen", "Shift+Ctrl+Numpad9", "Alt+Slash"] {
+            assert_eq!(hotkey(text).to_string(), text);
+            assert_eq!(hotkey(&hotkey(text).to_string()), hotkey(text));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_combinations() {
+        for text in ["", "Ctrl+", "Ctrl++A", "Ctrl+Shift", "A+Ctrl", "Ctrl+A+B", "Ctrl+ShiftLeft", "Ctrl+Bogus"] {
+            assert!(Hotkey::parse(text).is_err(), "'{}' should be rejected", text);
+        }
+        assert!(hotkey("F9").validate().is_err());
+        assert!(hotkey("Ctrl+F9").validate().is_ok());
+    }
+
+    #[test]
+    fn serializes_as_text() {
+        let json = serde_json::to_string(&hotkey("Super+Shift+C")).unwrap();
+        assert_eq!(json, "\"Super+Shift+C\"");
+        assert_eq!(serde_json::from_str::<Hotkey>(&json).unwrap(), hotkey("Super+Shift+C"));
+        assert!(serde_json::from_str::<Hotkey>("\"Ctrl+Nope\"").is_err());
+    }
+
+    #[test]
+    fn plus_records_the_main_row_key() {
+        let plus = Hotkey::from_egui(egui::Key::Plus, egui::Modifiers::CTRL, false).unwrap();
+        assert_eq!(plus.code, Code::Equal);
+
+        let letter = Hotkey::from_egui(egui::Key::G, egui::Modifiers::SHIFT, true).unwrap();
+        assert_eq!(letter, hotkey("Super+Shift+G"));
+    }
+}
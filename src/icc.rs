@@ -0,0 +1,285 @@
+use qcms::{DataType, Intent, Profile, Transform};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use xcap::Monitor;
+
+use crate::config::Config;
+
+/// Color space that managed values are reported in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ManagedSpace {
+    #[default]
+    Srgb,
+    DisplayP3,
+}
+
+impl ManagedSpace {
+    pub fn label(self) -> &'static str {
+        match self {
+            ManagedSpace::Srgb => "sRGB",
+            ManagedSpace::DisplayP3 => "P3",
+        }
+    }
+
+    fn profile(self) -> Option<Box<Profile>> {
+        match self {
+            ManagedSpace::Srgb => Some(Profile::new_sRGB()),
+            ManagedSpace::DisplayP3 => display_p3(),
+        }
+    }
+}
+
+/// Converts raw framebuffer samples from calibrated monitors into a standard
+/// color space, using each monitor's ICC profile
+pub struct ColorManagement {
+    target: ManagedSpace,
+    monitors: Vec<ManagedMonitor>,
+}
+
+struct ManagedMonitor {
+    name: String,
+    /// Desktop rectangle, in the same units as `Capture::offset`
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    source: String,
+    transform: Transform,
+}
+
+impl ColorManagement {
+    /// Sets up conversions for every monitor that has a profile, `None` when
+    /// none has one and raw values are all there is.
+    ///
+    /// A monitor's profile comes from `monitor_icc_profiles`, then
+    /// `icc_profile`, then the X11 `_ICC_PROFILE` atom that colord and most
+    /// desktop color settings publish.
+    pub fn load(config: &Config) -> Option<Self> {
+        let target = config.managed_space.profile()?;
+        let monitors = Monitor::all().ok()?;
+
+        let monitors: Vec<ManagedMonitor> = monitors
+            .iter()
+            .enumerate()
+            .filter_map(|(index, monitor)| {
+                let (profile, source) = match monitor_profile(config, monitor.name(), index) {
+                    Ok(found) => found?,
+                    Err(e) => {
+                        eprintln!("⚠️  {}", e);
+                        return None;
+                    }
+                };
+                let transform = Transform::new(&profile, &target, DataType::RGB8, Intent::RelativeColorimetric)?;
+
+                Some(ManagedMonitor {
+                    name: monitor.name().to_string(),
+                    x: monitor.x(),
+                    y: monitor.y(),
+                    width: monitor.width(),
+                    height: monitor.height(),
+                    source,
+                    transform,
+                })
+            })
+            .collect();
+
+        if monitors.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            target: config.managed_space,
+            monitors,
+        })
+    }
+
+    pub fn target(&self) -> ManagedSpace {
+        self.target
+    }
+
+    /// The managed value of a raw color sampled at desktop position `(x, y)`,
+    /// `None` if that monitor has no profile
+    pub fn convert(&self, (x, y): (i32, i32), (r, g, b): (u8, u8, u8)) -> Option<(u8, u8, u8)> {
+        let monitor = self.monitors.iter().find(|m| {
+            x >= m.x && y >= m.y && x < m.x + m.width as i32 && y < m.y + m.height as i32
+        })?;
+
+        let mut pixel = [r, g, b];
+        monitor.transform.apply(&mut pixel);
+        Some((pixel[0], pixel[1], pixel[2]))
+    }
+
+    /// One line per managed monitor, for `yoinkctl doctor`
+    pub fn describe(&self) -> Vec<String> {
+        self.monitors
+            .iter()
+            .map(|m| format!("{} → {} ({})", m.name, self.target.label(), m.source))
+            .collect()
+    }
+}
+
+/// Checks that a file is an ICC profile qcms can use, for validating settings
+pub fn check_profile(path: &Path) -> Result<(), String> {
+    load_profile(path).map(|_| ())
+}
+
+fn load_profile(path: &Path) -> Result<Box<Profile>, String> {
+    let data = std::fs::read(path)
+        .map_err(|e| format!("Failed to read ICC profile {}: {}", path.display(), e))?;
+
+    Profile::new_from_slice(&data, false)
+        .ok_or_else(|| format!("{} is not a usable RGB ICC profile", path.display()))
+}
+
+fn monitor_profile(config: &Config, name: &str, index: usize) -> Result<Option<(Box<Profile>, String)>, String> {
+    if let Some(path) = config.monitor_icc_profiles.get(name).or(config.icc_profile.as_ref()) {
+        return load_profile(path).map(|profile| Some((profile, path.display().to_string())));
+    }
+
+    // Each Xinerama screen after the first gets its own numbered atom
+    let atom = match index {
+        0 => "_ICC_PROFILE".to_string(),
+        n => format!("_ICC_PROFILE_{}", n),
+    };
+    Ok(x11::root_property(&atom)
+        .and_then(|data| Profile::new_from_slice(&data, false))
+        .map(|profile| (profile, format!("X11 {}", atom))))
+}
+
+/// Display P3: DCI-P3 primaries with a D65 white point and the sRGB curve
+fn display_p3() -> Option<Box<Profile>> {
+    let xy = |x, y| qcms::CIE_xyY { x, y, Y: 1.0 };
+    let primaries = qcms::CIE_xyYTRIPLE {
+        red: xy(0.680, 0.320),
+        green: xy(0.265, 0.690),
+        blue: xy(0.150, 0.060),
+    };
+
+    let table: Vec<u16> = (0..1024)
+        .map(|i| {
+            let linear = crate::color::srgb_to_linear_f32(i as f32 / 1023.0);
+            (linear * 65535.0).round() as u16
+        })
+        .collect();
+
+    Profile::new_rgb_with_table(xy(0.3127, 0.3290), primaries, &table)
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use std::ffi::CString;
+    use std::os::raw::{c_int, c_uchar, c_ulong};
+    use x11_dl::xlib;
+
+    /// Raw bytes of a property on the root window, `None` if it isn't set
+    pub fn root_property(name: &str) -> Option<Vec<u8>> {
+        let xlib = xlib::Xlib::open().ok()?;
+        let name = CString::new(name).ok()?;
+
+        unsafe {
+            let display = (xlib.XOpenDisplay)(std::ptr::null());
+            if display.is_null() {
+                return None;
+            }
+
+            let atom = (xlib.XInternAtom)(display, name.as_ptr(), xlib::True);
+            let mut data = None;
+
+            if atom != 0 {
+                let mut actual_type = 0;
+                let mut format: c_int = 0;
+                let mut items: c_ulong = 0;
+                let mut remaining: c_ulong = 0;
+                let mut bytes: *mut c_uchar = std::ptr::null_mut();
+
+                let status = (xlib.XGetWindowProperty)(
+                    display,
+                    (xlib.XDefaultRootWindow)(display),
+                    atom,
+                    0,
+                    // In 32-bit units, comfortably larger than any ICC profile
+                    16 * 1024 * 1024,
+                    xlib::False,
+                    xlib::AnyPropertyType as c_ulong,
+                    &mut actual_type,
+                    &mut format,
+                    &mut items,
+                    &mut remaining,
+                    &mut bytes,
+                );
+
+                if status == xlib::Success as c_int && !bytes.is_null() {
+                    if format == 8 && items > 0 {
+                        data = Some(std::slice::from_raw_parts(bytes, items as usize).to_vec());
+                    }
+                    (xlib.XFree)(bytes as *mut _);
+                }
+            }
+
+            (xlib.XCloseDisplay)(display);
+            data
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod x11 {
+    pub fn root_property(_name: &str) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One 100x100 monitor at the origin, with `profile` converted to `target`
+    fn single_monitor(profile: Box<Profile>, target: ManagedSpace) -> ColorManagement {
+        let transform = Transform::new(&profile, &target.profile().unwrap(), DataType::RGB8, Intent::RelativeColorimetric).unwrap();
+        ColorManagement {
+            target,
+            monitors: vec![ManagedMonitor {
+                name: "test".to_string(),
+                x: 0,
+                y: 0,
+                width: 100,
+                height: 100,
+                source: "test".to_string(),
+                transform,
+            }],
+        }
+    }
+
+    fn assert_close(actual: (u8, u8, u8), expected: (u8, u8, u8)) {
+        let near = |a: u8, b: u8| a.abs_diff(b) <= 2;
+        assert!(
+            near(actual.0, expected.0) && near(actual.1, expected.1) && near(actual.2, expected.2),
+            "{:?} is not close to {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn srgb_red_in_display_p3() {
+        let managed = single_monitor(Profile::new_sRGB(), ManagedSpace::DisplayP3);
+        assert_close(managed.convert((10, 10), (255, 0, 0)).unwrap(), (234, 51, 35));
+    }
+
+    #[test]
+    fn display_p3_back_to_srgb() {
+        let managed = single_monitor(display_p3().unwrap(), ManagedSpace::Srgb);
+        assert_close(managed.convert((10, 10), (234, 51, 35)).unwrap(), (255, 0, 0));
+        // P3 red is outside sRGB and clips to its red
+        assert_close(managed.convert((10, 10), (255, 0, 0)).unwrap(), (255, 0, 0));
+        assert_close(managed.convert((10, 10), (128, 128, 128)).unwrap(), (128, 128, 128));
+    }
+
+    #[test]
+    fn positions_off_managed_monitors_stay_raw() {
+        let managed = single_monitor(display_p3().unwrap(), ManagedSpace::Srgb);
+        assert_eq!(managed.convert((100, 10), (1, 2, 3)), None);
+        assert_eq!(managed.convert((-1, 10), (1, 2, 3)), None);
+    }
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Commands understood by the daemon's control socket.
+///
+/// The protocol is one plain-text command per connection (`status\n`),
+/// answered with a single line of JSON, so it can be driven from a shell:
+/// `echo status | socat - UNIX-CONNECT:$XDG_RUNTIME_DIR/yoinkctl/daemon.sock`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Status,
+    Version,
+    Pick,
+    Reload,
+    Stop,
+    /// `copy [--primary] TEXT`, the daemon takes over serving the clipboard
+    /// so it outlives the picker process that copied
+    Copy { text: String, primary: bool },
+}
+
+impl Request {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (command, argument) = text.split_once(' ').unwrap_or((text, ""));
+
+        match command.to_lowercase().as_str() {
+            "status" => Ok(Self::Status),
+            "version" => Ok(Self::Version),
+            "pick" => Ok(Self::Pick),
+            "reload" => Ok(Self::Reload),
+            "stop" => Ok(Self::Stop),
+            "copy" => {
+                let (primary, text) = match argument.strip_prefix("--primary ") {
+                    Some(text) => (true, text),
+                    None => (false, argument),
+                };
+                if text.is_empty() {
+                    return Err("copy needs the text to copy".to_string());
+                }
+                Ok(Self::Copy { text: text.to_string(), primary })
+            }
+            other => Err(format!("Unknown command '{}' (expected status, version, pick, reload, stop or copy)", other)),
+        }
+    }
+
+    pub fn to_line(&self) -> String {
+        match self {
+            Self::Status => "status".to_string(),
+            Self::Version => "version".to_string(),
+            Self::Pick => "pick".to_string(),
+            Self::Reload => "reload".to_string(),
+            Self::Stop => "stop".to_string(),
+            Self::Copy { text, primary: true } => format!("copy --primary {}", text),
+            Self::Copy { text, primary: false } => format!("copy {}", text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hotkey: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl Response {
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self {
+            ok: true,
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+}
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Per-user directory for sockets and locks, `$XDG_RUNTIME_DIR/yoinkctl`
+pub fn runtime_dir() -> PathBuf {
+    let mut path = dirs::runtime_dir()
+        .or_else(dirs::cache_dir)
+        .unwrap_or_else(std::env::temp_dir);
+    path.push("yoinkctl");
+    create_private_dir(&path);
+    path
+}
+
+pub fn socket_path() -> PathBuf {
+    runtime_dir().join("daemon.sock")
+}
+
+fn create_private_dir(path: &std::path::Path) {
+    std::fs::create_dir_all(path).ok();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o700)).ok();
+    }
+}
+
+/// Sends a single request to the running daemon and waits for its answer
+#[cfg(unix)]
+pub fn send(request: Request) -> Result<Response, String> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket_path())
+        .map_err(|e| format!("Daemon is not running ({})", e))?;
+    stream.set_read_timeout(Some(TIMEOUT)).ok();
+    stream.set_write_timeout(Some(TIMEOUT)).ok();
+
+    writeln!(stream, "{}", request.to_line())
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+
+    let response: Response = serde_json::from_str(&line)
+        .map_err(|e| format!("Invalid response from daemon: {}", e))?;
+
+    if response.ok {
+        Ok(response)
+    } else {
+        Err(response.message)
+    }
+}
+
+#[cfg(not(unix))]
+pub fn send(_request: Request) -> Result<Response, String> {
+    Err("The daemon control socket is only available on Unix".to_string())
+}
+
+/// Binds the control socket, replacing a stale socket file left by a crashed daemon.
+///
+/// Fails if another daemon is still listening on it, even one too busy to answer.
+#[cfg(unix)]
+pub fn bind() -> Result<std::os::unix::net::UnixListener, String> {
+    use std::io::ErrorKind;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    let path = socket_path();
+
+    // Only a refused connection means nobody is listening anymore
+    match UnixStream::connect(&path) {
+        Ok(_) => {
+            return Err(match send(Request::Status) {
+                Ok(response) => format!("Daemon already running (pid {})", response.pid.unwrap_or_default()),
+                Err(_) => format!("Daemon already running but not answering on {}", path.display()),
+            });
+        }
+        Err(e) if e.kind() == ErrorKind::ConnectionRefused => {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove stale socket {}: {}", path.display(), e))?;
+        }
+        Err(_) => {}
+    }
+
+    UnixListener::bind(&path)
+        .map_err(|e| format!("Failed to bind control socket {}: {}", path.display(), e))
+}
+
+/// Serves requests on `listener`, passing each to `handle` and writing back its response.
+///
+/// Runs until the listener fails, so it's meant to live on its own thread.
+#[cfg(unix)]
+pub fn serve<F>(listener: std::os::unix::net::UnixListener, handle: F)
+where
+    F: Fn(Request) -> Response,
+{
+    use std::io::{BufRead, BufReader, Write};
+
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+        stream.set_read_timeout(Some(TIMEOUT)).ok();
+        stream.set_write_timeout(Some(TIMEOUT)).ok();
+
+        let mut line = String::new();
+        let Ok(reader) = stream.try_clone() else {
+            continue;
+        };
+        if BufReader::new(reader).read_line(&mut line).is_err() {
+            continue;
+        }
+
+        let response = match Request::parse(&line) {
+            Ok(request) => handle(request),
+            Err(e) => Response::error(e),
+        };
+
+        if let Ok(json) = serde_json::to_string(&response) {
+            writeln!(stream, "{}", json).ok();
+        }
+    }
+}
+
+pub fn remove_socket() {
+    std::fs::remove_file(socket_path()).ok();
+}
\ No newline at end of file
@@ -0,0 +1,139 @@
+use std::fs::File;
+use std::path::PathBuf;
+
+use crate::ipc;
+
+/// Holds the single-instance picker lock for as long as it's alive.
+///
+/// The lock is an advisory `flock`, so the kernel drops it when the process
+/// exits for any reason (including panics and SIGKILL) and there is never a
+/// stale lock file to clean up.
+pub struct PickerLock {
+    file: File,
+}
+
+impl Drop for PickerLock {
+    fn drop(&mut self) {
+        // Cleared while still locked, so the next picker never reads our PID
+        // once it might belong to another process
+        self.file.set_len(0).ok();
+    }
+}
+
+pub enum LockOutcome {
+    Acquired(PickerLock),
+    /// Another picker holds the lock, with its PID if it could be read
+    HeldBy(Option<u32>),
+}
+
+/// How often a second picker re-reads the PID of one that is just starting
+#[cfg(unix)]
+const PID_ATTEMPTS: u32 = 20;
+
+pub fn lock_path() -> PathBuf {
+    ipc::runtime_dir().join("picker.lock")
+}
+
+#[cfg(unix)]
+pub fn acquire() -> Result<LockOutcome, String> {
+    use std::io::{Read, Seek, Write};
+    use std::os::unix::io::AsRawFd;
+
+    let path = lock_path();
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .map_err(|e| format!("Failed to open picker lock {}: {}", path.display(), e))?;
+
+    let result = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+
+    if result != 0 {
+        let error = std::io::Error::last_os_error();
+        if error.kind() != std::io::ErrorKind::WouldBlock {
+            return Err(format!("Failed to lock {}: {}", path.display(), error));
+        }
+
+        // The holder writes its PID right after locking and clears it before
+        // unlocking, an empty file means we caught it in between
+        for _ in 0..PID_ATTEMPTS {
+            let mut content = String::new();
+            file.rewind().ok();
+            file.read_to_string(&mut content).ok();
+            if let Ok(pid) = content.trim().parse() {
+                return Ok(LockOutcome::HeldBy(Some(pid)));
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        return Ok(LockOutcome::HeldBy(None));
+    }
+
+    // Record our PID so a second picker knows whom to signal
+    file.set_len(0).ok();
+    file.rewind().ok();
+    write!(file, "{}", std::process::id()).ok();
+    file.flush().ok();
+
+    Ok(LockOutcome::Acquired(PickerLock { file }))
+}
+
+#[cfg(not(unix))]
+pub fn acquire() -> Result<LockOutcome, String> {
+    Err("Picker locking is only available on Unix".to_string())
+}
+
+/// Asks the picker holding the lock to close, so pressing the hotkey again
+/// toggles the overlay off
+#[cfg(unix)]
+pub fn cancel_running_picker(pid: u32) -> Result<(), String> {
+    // A picker that crashed leaves its PID behind, and SIGUSR1 kills
+    // whatever process reuses it
+    if !is_yoinkctl(pid) {
+        return Err(format!("pid {} in the picker lock isn't yoinkctl anymore, not signalling it", pid));
+    }
+
+    let result = unsafe { libc::kill(pid as libc::pid_t, libc::SIGUSR1) };
+
+    if result != 0 {
+        return Err(format!("Failed to signal picker (pid {}): {}", pid, std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+/// Whether `pid` runs the same executable as this process
+#[cfg(target_os = "linux")]
+fn is_yoinkctl(pid: u32) -> bool {
+    let executable = |path: PathBuf| {
+        // Replaced binaries show up as "/path/yoinkctl (deleted)"
+        path.to_string_lossy().trim_end_matches(" (deleted)").to_string()
+    };
+
+    match (std::fs::read_link(format!("/proc/{}/exe", pid)), std::env::current_exe()) {
+        (Ok(theirs), Ok(ours)) => executable(theirs) == executable(ours),
+        _ => false,
+    }
+}
+
+/// There's no /proc to ask, the cleared lock file has to do
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_yoinkctl(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(not(unix))]
+pub fn cancel_running_picker(_pid: u32) -> Result<(), String> {
+    Err("Cancelling a running picker is only available on Unix".to_string())
+}
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_only_our_own_executable() {
+        assert!(is_yoinkctl(std::process::id()));
+        assert!(!is_yoinkctl(1));
+        assert!(!is_yoinkctl(u32::MAX));
+    }
+}
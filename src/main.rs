@@ -1,18 +1,39 @@
 use eframe::egui;
 use std::env;
-use std::process::Command;
-use global_hotkey::{GlobalHotKeyManager, hotkey::HotKey, GlobalHotKeyEvent};
-use xcap::Monitor;
-use image::RgbaImage;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 
 mod picker;
+mod capture;
+mod color;
+mod harmony;
+mod notation;
+mod theme;
+mod icc;
+mod palette;
 mod config;
+mod clipboard;
 mod autostart;
 mod history;
+mod hotkey;
+mod diagnostics;
+mod portal;
+mod notify;
+mod ipc;
+mod daemon;
+mod resident;
+mod lock;
 mod gui;
+#[cfg(all(test, target_os = "linux"))]
+mod testing;
 
 use picker::ColorPicker;
+use capture::CaptureBackend;
 use config::Config;
+use diagnostics::{DaemonState, DaemonStatus};
+use daemon::run_daemon;
+use lock::LockOutcome;
 use gui::ConfigApp;
 
 fn main() -> Result<(), eframe::Error> {
@@ -21,11 +42,82 @@ fn main() -> Result<(), eframe::Error> {
     if args.len() > 1 {
         match args[1].as_str() {
             "pick" => {
-                return run_picker();
+                return match parse_pick_args(&args[2..]) {
+                    Ok(PickArgs { image: Some(path), at: Some(at) }) => {
+                        if !run_pick_at(&path, at) {
+                            std::process::exit(1);
+                        }
+                        Ok(())
+                    }
+                    Ok(PickArgs { image: Some(path), at: None }) => run_image_picker(path),
+                    Ok(PickArgs { image: None, .. }) => run_picker(),
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        eprintln!("Usage: yoinkctl pick [--image PATH [--at X,Y]]");
+                        std::process::exit(2);
+                    }
+                };
+            }
+            "palette" => {
+                if !run_palette(&args[2..]) {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            "convert" => {
+                match parse_convert_args(&args[2..]) {
+                    Ok((color, format)) => {
+                        if !run_convert(&color, format) {
+                            std::process::exit(1);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        eprintln!("Usage: yoinkctl convert COLOR [--to hex|rgb|hsl|oklch|oklab|name|all]");
+                        std::process::exit(2);
+                    }
+                }
+                return Ok(());
             }
             "daemon" => {
                 if let Err(e) = run_daemon() {
                     eprintln!("Daemon error: {}", e);
+                    let hotkey = Config::load().unwrap_or_default().hotkey;
+                    DaemonStatus::failed(&hotkey, e).save().ok();
+                    // Non-zero so a systemd unit's Restart=on-failure kicks in
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            "ctl" => {
+                // `yoinkctl ctl copy '#ff0000'` arrives as separate words
+                let command = match args[2..].join(" ") {
+                    command if command.trim().is_empty() => "status".to_string(),
+                    command => command,
+                };
+                if !run_ctl(&command) {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            // Internal: keeps a copied value alive after the picker exits, see clipboard.rs
+            "clipboard-serve" => {
+                let primary = args[2..].iter().any(|arg| arg == "--primary");
+                // `--image WIDTHxHEIGHT` means stdin is raw RGBA instead of text
+                let image_size = args[2..].iter()
+                    .skip_while(|arg| *arg != "--image")
+                    .nth(1)
+                    .and_then(|size| size.split_once('x'))
+                    .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)));
+                if let Err(e) = clipboard::serve_until_replaced(primary, image_size) {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            "doctor" => {
+                if !run_doctor() {
+                    std::process::exit(1);
                 }
                 return Ok(());
             }
@@ -36,164 +128,448 @@ fn main() -> Result<(), eframe::Error> {
     run_config_gui()
 }
 
-fn run_daemon() -> Result<(), String> {
-    let config = Config::load().unwrap_or_default();
+/// Sends a single command to the running daemon and prints its JSON answer
+fn run_ctl(command: &str) -> bool {
+    let result = ipc::Request::parse(command).and_then(ipc::send);
+    
+    match result {
+        Ok(response) => {
+            println!("{}", serde_json::to_string_pretty(&response).unwrap_or_default());
+            true
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            false
+        }
+    }
+}
+
+/// Prints a diagnostic report and returns whether everything looks healthy
+fn run_doctor() -> bool {
+    let mut healthy = true;
+    
+    println!("🩺 yoinkctl doctor");
+    println!();
+    println!("Session:  {}", diagnostics::session_type());
     
-    println!("🚀 yoinkctl daemon starting...");
-    println!("📌 Hotkey: {}", config.hotkey);
+    match autostart::Autostart::new().active_backend() {
+        Some(autostart::AutostartBackend::Systemd) => println!(
+            "Startup:  {} ({})",
+            autostart::AutostartBackend::Systemd.label(),
+            autostart::systemd_status().unwrap_or_else(|| "unknown".to_string())
+        ),
+        Some(backend) => println!("Startup:  {}", backend.label()),
+        None => println!("Startup:  autostart off"),
+    }
     
-    let manager = GlobalHotKeyManager::new()
-        .map_err(|e| format!("Failed to create hotkey manager: {}", e))?;
+    let config = match Config::load() {
+        Ok(config) => {
+            println!("Config:   ✅ {}", Config::config_path().display());
+            config
+        }
+        Err(e) => {
+            println!("Config:   ❌ {} (using defaults)", e);
+            healthy = false;
+            Config::default()
+        }
+    };
     
-    let modifiers = config.get_modifiers();
-    let key_code = config.get_key_code();
+    if portal::should_use() {
+        for interface in ["Screenshot", "GlobalShortcuts"] {
+            match portal::interface_version(&format!("org.freedesktop.portal.{}", interface)) {
+                Ok(version) => println!("Portal:   ✅ {} v{}", interface, version),
+                Err(e) => println!("Portal:   ⚠️  {}", e),
+            }
+        }
+    }
     
-    if modifiers.is_empty() {
-        return Err("Hotkey must have at least one modifier (Super, Shift, Ctrl, or Alt)".to_string());
+    match icc::ColorManagement::load(&config) {
+        Some(color_management) => {
+            for line in color_management.describe() {
+                println!("Color:    ✅ {}", line);
+            }
+        }
+        None => println!("Color:    ○ no ICC profiles, values are raw framebuffer bytes"),
     }
     
-    let hotkey = HotKey::new(Some(modifiers), key_code);
-    let hotkey_id = hotkey.id();
+    println!("Hotkey:   {}", config.hotkey);
+    if let Err(e) = config.validate_hotkey() {
+        println!("          ❌ {}", e);
+        healthy = false;
+    }
     
-    // FORCE REGISTER: Try to unregister first, then register
-    let _ = manager.unregister(hotkey);
+    // A live daemon holds the grab itself, so only probe when it isn't running
+    if let Ok(response) = ipc::send(ipc::Request::Status) {
+        let hotkey = response.hotkey.unwrap_or_default();
+        println!("Daemon:   ✅ running (pid {}) with {}", response.pid.unwrap_or_default(), hotkey);
+        if hotkey != config.hotkey.to_string() {
+            println!("          ⚠️  config changed to {}, run `yoinkctl ctl reload` to apply", config.hotkey);
+        }
+        return healthy;
+    }
     
-    match manager.register(hotkey) {
-        Ok(_) => {
-            println!("✅ Hotkey registered! Press {} to pick colors", config.hotkey);
+    match DaemonStatus::load().ok().flatten() {
+        Some(status) if status.state == DaemonState::Failed => {
+            println!("Daemon:   ❌ last run failed: {}", status.message.unwrap_or_default());
+            healthy = false;
         }
+        _ => println!("Daemon:   ○ not running"),
+    }
+    
+    match diagnostics::probe_hotkey(&config.hotkey) {
+        Ok(()) => println!("Grab:     ✅ {} is free", config.hotkey),
         Err(e) => {
-            eprintln!("⚠️  First registration failed ({}), forcing...", e);
-            std::thread::sleep(std::time::Duration::from_millis(100));
+            println!("Grab:     ❌ {}", e);
+            healthy = false;
             
-            let _ = manager.unregister(hotkey);
-            std::thread::sleep(std::time::Duration::from_millis(100));
-            
-            manager.register(hotkey)
-                .map_err(|e| {
-                    eprintln!("❌ Failed to force register hotkey '{}'", config.hotkey);
-                    eprintln!("   Error: {}", e);
-                    format!("Hotkey conflict: {}", e)
-                })?;
-            
-            println!("✅ Hotkey forcefully registered!");
+            let suggestions = diagnostics::suggest_alternatives(&config.hotkey, 3);
+            if !suggestions.is_empty() {
+                println!("          Free alternatives:");
+                for hotkey in suggestions {
+                    println!("            • {}", hotkey);
+                }
+            }
         }
     }
     
-    let exe_path = env::current_exe()
-        .map_err(|e| format!("Failed to get exe path: {}", e))?;
-    let receiver = GlobalHotKeyEvent::receiver();
-    let mut last_activation = std::time::Instant::now();
-    
-    loop {
-        // Use try_recv for non-blocking check
-        match receiver.try_recv() {
-            Ok(event) => {
-                // CRITICAL: Always drain Release events to prevent state stuck
-                // This prevents the X11 key release order bug
-                if event.state == global_hotkey::HotKeyState::Released {
-                    continue;
-                }
-                
-                // Only process PRESSED events for our hotkey
-                if event.id != hotkey_id {
-                    continue;
-                }
-                
-                let now = std::time::Instant::now();
-                
-                // Minimal debounce - just enough to prevent accidental double-press
-                if now.duration_since(last_activation).as_millis() < 50 {
-                    continue;
-                }
-                
-                last_activation = now;
-                
-                // Launch picker immediately - let the picker handle its own locking
-                // This prevents the daemon from being blocked by stale lock checks
-                Command::new(&exe_path)
-                    .arg("pick")
-                    .stdin(std::process::Stdio::null())
-                    .stdout(std::process::Stdio::null())
-                    .stderr(std::process::Stdio::null())
-                    .spawn()
-                    .ok();
+    healthy
+}
+
+struct PickArgs {
+    image: Option<PathBuf>,
+    at: Option<(u32, u32)>,
+}
+
+fn parse_pick_args(args: &[String]) -> Result<PickArgs, String> {
+    let mut pick = PickArgs { image: None, at: None };
+    let mut args = args.iter();
+    
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--image" => {
+                let path = args.next().ok_or("--image needs a path")?;
+                pick.image = Some(PathBuf::from(path));
             }
-            Err(_) => {
-                // No event available - sleep a bit to reduce CPU usage
-                std::thread::sleep(std::time::Duration::from_millis(10));
+            "--at" => {
+                let at = args.next().ok_or("--at needs X,Y")?;
+                let (x, y) = at.split_once(',').ok_or_else(|| format!("'{}' is not X,Y", at))?;
+                let x = x.trim().parse().map_err(|_| format!("Invalid X coordinate '{}'", x))?;
+                let y = y.trim().parse().map_err(|_| format!("Invalid Y coordinate '{}'", y))?;
+                pick.at = Some((x, y));
             }
+            other => return Err(format!("Unknown pick option '{}'", other)),
         }
     }
+    
+    if pick.at.is_some() && pick.image.is_none() {
+        return Err("--at only works together with --image".to_string());
+    }
+    
+    Ok(pick)
 }
 
-fn run_picker() -> Result<(), eframe::Error> {
-    let lock_path = std::env::temp_dir().join("yoinkctl-picker.lock");
-    
-    // ATOMIC LOCK: Create file and write PID immediately
-    let lock_acquired = match std::fs::OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open(&lock_path)
-    {
-        Ok(mut file) => {
-            use std::io::Write;
-            let pid = std::process::id();
-            write!(file, "{}", pid).ok();
-            file.flush().ok();
-            file.sync_all().ok();
-            drop(file);
-            true
+/// Prints the color at one pixel of an image, for scripts
+fn run_pick_at(path: &Path, (x, y): (u32, u32)) -> bool {
+    let backend = capture::FileBackend { path: path.to_path_buf() };
+    let capture = match backend.capture() {
+        Ok(capture) => capture,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return false;
         }
-        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
-            // Lock exists - verify if process is alive
-            if let Ok(pid_str) = std::fs::read_to_string(&lock_path) {
-                if let Ok(pid) = pid_str.trim().parse::<i32>() {
-                    #[cfg(target_os = "linux")]
-                    {
-                        if std::path::Path::new(&format!("/proc/{}", pid)).exists() {
-                            return Ok(()); // Already running
-                        }
-                    }
-                }
+    };
+    let image = &capture.image;
+    
+    if x >= image.width() || y >= image.height() {
+        eprintln!("❌ ({}, {}) is outside the {}×{} image", x, y, image.width(), image.height());
+        return false;
+    }
+    
+    let pixel = image.get_pixel(x, y);
+    let alpha = capture.alpha.then_some(pixel[3]);
+    println!("{}", color::hex_string((pixel[0], pixel[1], pixel[2]), alpha));
+    true
+}
+
+struct PaletteArgs {
+    image: Option<PathBuf>,
+    region: Option<(i32, i32, u32, u32)>,
+    count: usize,
+    method: palette::Method,
+    name: Option<String>,
+    save: bool,
+}
+
+fn parse_palette_args(args: &[String]) -> Result<PaletteArgs, String> {
+    let mut palette = PaletteArgs {
+        image: None,
+        region: None,
+        count: 6,
+        method: palette::Method::KMeans,
+        name: None,
+        save: true,
+    };
+    let mut args = args.iter();
+    
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--image" => palette.image = Some(PathBuf::from(value()?)),
+            "--region" => {
+                let region = value()?;
+                let parts: Vec<&str> = region.split(',').map(str::trim).collect();
+                let [x, y, w, h] = parts[..] else {
+                    return Err(format!("'{}' is not X,Y,W,H", region));
+                };
+                let invalid = |_| format!("Invalid region '{}'", region);
+                palette.region = Some((
+                    x.parse().map_err(invalid)?,
+                    y.parse().map_err(invalid)?,
+                    w.parse().map_err(invalid)?,
+                    h.parse().map_err(invalid)?,
+                ));
             }
-            
-            // Stale lock - remove and retry ONCE
-            std::fs::remove_file(&lock_path).ok();
-            
-            match std::fs::OpenOptions::new()
-                .write(true)
-                .create_new(true)
-                .open(&lock_path)
-            {
-                Ok(mut file) => {
-                    use std::io::Write;
-                    let pid = std::process::id();
-                    write!(file, "{}", pid).ok();
-                    file.flush().ok();
-                    file.sync_all().ok();
-                    drop(file);
-                    true
-                }
-                Err(_) => return Ok(()), // Another instance won
+            "-n" | "--count" => {
+                let count = value()?;
+                palette.count = count.parse()
+                    .ok()
+                    .filter(|n| (1..=32).contains(n))
+                    .ok_or_else(|| format!("Color count must be 1-32, got '{}'", count))?;
             }
+            "--method" => palette.method = value()?.parse()?,
+            "--name" => palette.name = Some(value()?.clone()),
+            "--no-save" => palette.save = false,
+            other => return Err(format!("Unknown palette option '{}'", other)),
         }
-        Err(_) => return Ok(()),
+    }
+    
+    Ok(palette)
+}
+
+/// The color text and target format of `yoinkctl convert`, `None` meaning
+/// every format. Unquoted colors like `rgb(1 2 3)` arrive split into words.
+fn parse_convert_args(args: &[String]) -> Result<(String, Option<notation::Format>), String> {
+    let mut words = Vec::new();
+    let mut format = None;
+    
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--to" => {
+                let value = args.next().ok_or("--to needs a format")?;
+                format = match value.as_str() {
+                    "all" => None,
+                    value => Some(value.parse()?),
+                };
+            }
+            _ => words.push(arg.as_str()),
+        }
+    }
+    
+    if words.is_empty() {
+        return Err("No color given".to_string());
+    }
+    Ok((words.join(" "), format))
+}
+
+fn run_convert(text: &str, format: Option<notation::Format>) -> bool {
+    let color = match notation::parse(text) {
+        Ok(color) => color,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return false;
+        }
+    };
+    
+    match format {
+        Some(format) => {
+            println!("{}", format.format(color));
+            if format == notation::Format::Name && !notation::nearest_name(color.rgb).1 {
+                eprintln!("⚠️  No exact name, this is the nearest one");
+            }
+        }
+        None => {
+            for format in notation::Format::ALL {
+                println!("{:<6} {}", format.label(), format.format(color));
+            }
+        }
+    }
+    true
+}
+
+/// Prints the dominant colors of the screen, a region of it or an image,
+/// and saves them as a named palette in the history
+fn run_palette(args: &[String]) -> bool {
+    let args = match parse_palette_args(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            eprintln!("Usage: yoinkctl palette [--image PATH] [--region X,Y,W,H] [-n COUNT] [--method kmeans|median-cut] [--name NAME] [--no-save]");
+            return false;
+        }
+    };
+    
+    let capture = match &args.image {
+        Some(path) => match (capture::FileBackend { path: path.clone() }).capture() {
+            Ok(capture) => capture,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                return false;
+            }
+        },
+        None => match capture::capture_screen() {
+            Some(capture) => capture,
+            None => {
+                eprintln!("❌ Could not capture the screen");
+                return false;
+            }
+        },
+    };
+    
+    let image = capture.image;
+    let image = match args.region {
+        Some((x, y, width, height)) => {
+            // Regions are in desktop coordinates, the capture may not start at
+            // 0,0 or be at one pixel per unit
+            let to_pixels = |value: i32| (value as f32 * capture.scale).round() as i32;
+            let (x, y) = (to_pixels(x - capture.offset.0), to_pixels(y - capture.offset.1));
+            let (width, height) = (to_pixels(width as i32), to_pixels(height as i32));
+            let left = x.clamp(0, image.width() as i32) as u32;
+            let top = y.clamp(0, image.height() as i32) as u32;
+            let right = (x + width).clamp(0, image.width() as i32) as u32;
+            let bottom = (y + height).clamp(0, image.height() as i32) as u32;
+            if right <= left || bottom <= top {
+                eprintln!("❌ Region is outside the {}×{} image", image.width(), image.height());
+                return false;
+            }
+            image::imageops::crop_imm(&image, left, top, right - left, bottom - top).to_image()
+        }
+        None => image,
     };
     
-    if !lock_acquired {
-        return Ok(());
+    let colors = palette::extract(&image, args.count, args.method);
+    if colors.is_empty() {
+        eprintln!("❌ No opaque pixels to build a palette from");
+        return false;
+    }
+    
+    for color in &colors {
+        println!("{}  {:5.1}%", color.hex, color.coverage * 100.0);
+    }
+    
+    if args.save {
+        let name = args.name.unwrap_or_else(|| match &args.image {
+            Some(path) => path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "Image".to_string()),
+            None => format!("Screen {}", chrono::Local::now().format("%Y-%m-%d %H:%M")),
+        });
+        
+        let mut history = history::ColorHistory::load().unwrap_or_default();
+        match history.add_palette(name.clone(), colors) {
+            Ok(()) => eprintln!("✅ Saved palette '{}'", name),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                return false;
+            }
+        }
     }
     
+    true
+}
+
+/// Opens the picker on an image file in a normal window, with pan and zoom
+fn run_image_picker(path: PathBuf) -> Result<(), eframe::Error> {
+    let backend = capture::FileBackend { path: path.clone() };
+    let capture = match backend.capture() {
+        Ok(capture) => capture,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+    let image = &capture.image;
+    
+    let title = path.file_name()
+        .map(|name| format!("yoinkctl – {}", name.to_string_lossy()))
+        .unwrap_or_else(|| "yoinkctl".to_string());
+    // Start at the image size, within reason; the picker fits it on the first frame
+    let size = [
+        (image.width() as f32).clamp(480.0, 1400.0),
+        (image.height() as f32).clamp(360.0, 900.0),
+    ];
+    
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title(title.clone())
+            .with_inner_size(size)
+            .with_min_inner_size([320.0, 240.0]),
+        ..Default::default()
+    };
+    
+    let result = eframe::run_native(
+        &title,
+        options,
+        Box::new(move |cc| {
+            let config = Config::load().unwrap_or_default();
+            Ok(Box::new(ColorPicker::new_for_image(cc, capture, config)))
+        }),
+    );
+    
+    picker::wait_for_picks();
+    result
+}
+
+fn run_picker() -> Result<(), eframe::Error> {
+    // Registered before taking the lock: once our PID is in the lock file, a
+    // second press may signal us, and SIGUSR1's default action would kill us
+    let cancel_requested = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGUSR1, cancel_requested.clone()).ok();
+    
+    // Held until the overlay closes, the kernel releases it even on a crash
+    let lock = match lock::acquire() {
+        Ok(LockOutcome::Acquired(lock)) => Some(lock),
+        // A second hotkey press closes the picker that's already open
+        Ok(LockOutcome::HeldBy(Some(pid))) => {
+            if let Err(e) = lock::cancel_running_picker(pid) {
+                eprintln!("⚠️  {}", e);
+            }
+            return Ok(());
+        }
+        Ok(LockOutcome::HeldBy(None)) => {
+            eprintln!("⚠️  Another picker holds the lock but hasn't written its PID, ignoring this press");
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("⚠️  {}, starting without single-instance lock", e);
+            None
+        }
+    };
+    
     // SPEED OPTIMIZATION: Parallel screenshot + config loading
     let screenshot_handle = std::thread::spawn(|| {
-        capture_all_screens()
+        capture::capture_screen()
     });
     
     let config_handle = std::thread::spawn(|| {
         Config::load().unwrap_or_default()
     });
     
+    // Wayland compositors may refuse screenshots, their own picker still works
+    let screenshot_handle = if portal::should_use() {
+        let captured = screenshot_handle.join().unwrap_or(None);
+        if captured.is_none() {
+            picker::pick_with_portal();
+            drop(lock);
+            picker::wait_for_picks();
+            return Ok(());
+        }
+        std::thread::spawn(move || captured)
+    } else {
+        screenshot_handle
+    };
+    
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_decorations(false)
@@ -214,36 +590,26 @@ fn run_picker() -> Result<(), eframe::Error> {
         options,
         Box::new(move |cc| {
             // Retrieve pre-loaded data from parallel threads
-            let (screenshot, offset) = screenshot_handle.join().unwrap_or((None, (0, 0)));
+            let capture = screenshot_handle.join().unwrap_or(None);
             let config = config_handle.join().unwrap_or_default();
             
             // OPTIMIZATION: Disable font rasterization delay by using default fonts
             // This speeds up first frame render significantly
             
-            Ok(Box::new(ColorPicker::new_with_config(cc, screenshot, offset, config)))
+            let mut picker = ColorPicker::new_with_config(cc, capture, config);
+            picker.set_cancel_flag(cancel_requested);
+            
+            Ok(Box::new(picker))
         }),
     );
     
-    // Always clean up lock file
-    std::fs::remove_file(&lock_path).ok();
+    // The overlay is gone, the next hotkey press can start a new picker while
+    // this one finishes copying and answering its notification
+    drop(lock);
+    picker::wait_for_picks();
     result
 }
 
-// Helper function for parallel screenshot capture
-fn capture_all_screens() -> (Option<RgbaImage>, (i32, i32)) {
-    if let Ok(monitors) = Monitor::all() {
-        if let Some(monitor) = monitors.first() {
-            if let Ok(image) = monitor.capture_image() {
-                let x_offset = monitor.x();
-                let y_offset = monitor.y();
-                return (Some(image), (x_offset, y_offset));
-            }
-        }
-    }
-    
-    (None, (0, 0))
-}
-
 fn run_config_gui() -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -257,12 +623,6 @@ fn run_config_gui() -> Result<(), eframe::Error> {
         "yoinkctl",
         options,
         Box::new(|cc| {
-            cc.egui_ctx.set_visuals(egui::Visuals {
-                window_rounding: egui::Rounding::same(0.0),
-                panel_fill: egui::Color32::from_rgb(18, 18, 20),
-                ..egui::Visuals::dark()
-            });
-            
             cc.egui_ctx.set_embed_viewports(false);
             
             Ok(Box::new(ConfigApp::new(cc)))
@@ -0,0 +1,413 @@
+use crate::color::{self, alpha_fraction, hex_string, Oklab, Oklch};
+
+/// A color read from text: 8-bit sRGB, plus alpha when the notation had one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedColor {
+    pub rgb: (u8, u8, u8),
+    /// `None` for fully opaque colors
+    pub alpha: Option<u8>,
+}
+
+impl ParsedColor {
+    pub fn opaque(rgb: (u8, u8, u8)) -> Self {
+        Self { rgb, alpha: None }
+    }
+}
+
+/// Notations colors can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Hex,
+    Rgb,
+    Hsl,
+    Oklch,
+    Oklab,
+    Name,
+}
+
+impl Format {
+    pub const ALL: [Format; 6] = [Format::Hex, Format::Rgb, Format::Hsl, Format::Oklch, Format::Oklab, Format::Name];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Format::Hex => "hex",
+            Format::Rgb => "rgb",
+            Format::Hsl => "hsl",
+            Format::Oklch => "oklch",
+            Format::Oklab => "oklab",
+            Format::Name => "name",
+        }
+    }
+
+    /// CSS text for `color`. Names fall back to the nearest named color.
+    pub fn format(self, color: ParsedColor) -> String {
+        let (r, g, b) = color.rgb;
+        let css_alpha = |separator: &str| color.alpha.map(|a| format!("{}{}", separator, alpha_fraction(a))).unwrap_or_default();
+
+        match self {
+            Format::Hex => hex_string(color.rgb, color.alpha),
+            Format::Rgb => match color.alpha {
+                Some(_) => format!("rgba({}, {}, {}{})", r, g, b, css_alpha(", ")),
+                None => format!("rgb({}, {}, {})", r, g, b),
+            },
+            Format::Hsl => {
+                let (h, s, l) = color::rgb_to_hsl(r, g, b);
+                match color.alpha {
+                    Some(_) => format!("hsla({:.0}, {:.0}%, {:.0}%{})", h, s * 100.0, l * 100.0, css_alpha(", ")),
+                    None => format!("hsl({:.0}, {:.0}%, {:.0}%)", h, s * 100.0, l * 100.0),
+                }
+            }
+            Format::Oklch => {
+                let lch = Oklch::from_rgb(color.rgb);
+                format!("oklch({:.1}% {:.3} {:.1}{})", lch.l * 100.0, lch.c, lch.h, css_alpha(" / "))
+            }
+            Format::Oklab => {
+                let lab = Oklab::from_rgb(color.rgb);
+                format!("oklab({:.1}% {:.3} {:.3}{})", lab.l * 100.0, lab.a, lab.b, css_alpha(" / "))
+            }
+            Format::Name => nearest_name(color.rgb).0.to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hex" => Ok(Format::Hex),
+            "rgb" | "rgba" => Ok(Format::Rgb),
+            "hsl" | "hsla" => Ok(Format::Hsl),
+            "oklch" => Ok(Format::Oklch),
+            "oklab" => Ok(Format::Oklab),
+            "name" | "named" => Ok(Format::Name),
+            _ => Err(format!("Unknown format '{}' (use hex, rgb, hsl, oklch, oklab or name)", s)),
+        }
+    }
+}
+
+/// Color spaces the converter has channel sliders for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SliderSpace {
+    Rgb,
+    Hsl,
+    Oklch,
+}
+
+impl SliderSpace {
+    pub const ALL: [SliderSpace; 3] = [SliderSpace::Rgb, SliderSpace::Hsl, SliderSpace::Oklch];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SliderSpace::Rgb => "RGB",
+            SliderSpace::Hsl => "HSL",
+            SliderSpace::Oklch => "OKLCH",
+        }
+    }
+
+    /// Name and range of each channel
+    pub fn channels(self) -> [(&'static str, std::ops::RangeInclusive<f32>); 3] {
+        match self {
+            SliderSpace::Rgb => [("R", 0.0..=255.0), ("G", 0.0..=255.0), ("B", 0.0..=255.0)],
+            SliderSpace::Hsl => [("H", 0.0..=360.0), ("S", 0.0..=100.0), ("L", 0.0..=100.0)],
+            SliderSpace::Oklch => [("L", 0.0..=1.0), ("C", 0.0..=0.4), ("H", 0.0..=360.0)],
+        }
+    }
+
+    pub fn decompose(self, (r, g, b): (u8, u8, u8)) -> [f32; 3] {
+        match self {
+            SliderSpace::Rgb => [r as f32, g as f32, b as f32],
+            SliderSpace::Hsl => {
+                let (h, s, l) = color::rgb_to_hsl(r, g, b);
+                [h, s * 100.0, l * 100.0]
+            }
+            SliderSpace::Oklch => {
+                let lch = Oklch::from_rgb((r, g, b));
+                [lch.l, lch.c, lch.h]
+            }
+        }
+    }
+
+    pub fn compose(self, [x, y, z]: [f32; 3]) -> (u8, u8, u8) {
+        match self {
+            SliderSpace::Rgb => (x.round() as u8, y.round() as u8, z.round() as u8),
+            SliderSpace::Hsl => color::hsl_to_rgb(x, y / 100.0, z / 100.0),
+            SliderSpace::Oklch => Oklch { l: x, c: y, h: z }.to_rgb(),
+        }
+    }
+}
+
+/// Parses hex (`#RGB`, `#RRGGBB` and their alpha forms, `#` optional),
+/// `rgb()`, `hsl()`, `oklch()`, `oklab()` in comma or space syntax, and CSS
+/// color names
+pub fn parse(text: &str) -> Result<ParsedColor, String> {
+    let text = text.trim().to_ascii_lowercase();
+    if text.is_empty() {
+        return Err("No color given".to_string());
+    }
+
+    if let Some((function, rest)) = text.split_once('(') {
+        let arguments = rest
+            .strip_suffix(')')
+            .ok_or_else(|| format!("Missing ')' in '{}'", text))?;
+        return parse_function(function.trim(), arguments);
+    }
+
+    if text == "transparent" {
+        return Ok(ParsedColor { rgb: (0, 0, 0), alpha: Some(0) });
+    }
+    if let Some(&(_, value)) = NAMED_COLORS.iter().find(|(name, _)| *name == text) {
+        return Ok(ParsedColor::opaque(unpack(value)));
+    }
+
+    parse_hex(text.strip_prefix('#').unwrap_or(&text))
+        .ok_or_else(|| format!("'{}' is not a color I can read", text))
+}
+
+/// The closest CSS color name, and whether it's an exact match
+pub fn nearest_name(rgb: (u8, u8, u8)) -> (&'static str, bool) {
+    let target = Oklab::from_rgb(rgb);
+    let (name, value) = NAMED_COLORS
+        .iter()
+        .min_by(|a, b| {
+            let distance = |value| Oklab::from_rgb(unpack(value)).distance_squared(target);
+            distance(a.1).total_cmp(&distance(b.1))
+        })
+        .copied()
+        .unwrap_or(("black", 0));
+    (name, unpack(value) == rgb)
+}
+
+fn parse_hex(digits: &str) -> Option<ParsedColor> {
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    // Short forms repeat each digit, `#f80` is `#ff8800`
+    let expanded: String = match digits.len() {
+        3 | 4 => digits.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => digits.to_string(),
+        _ => return None,
+    };
+    let byte = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).ok();
+
+    Some(with_alpha((byte(0)?, byte(2)?, byte(4)?), if expanded.len() == 8 { byte(6) } else { None }))
+}
+
+fn parse_function(function: &str, arguments: &str) -> Result<ParsedColor, String> {
+    // `rgb(1, 2, 3, 0.5)`, `rgb(1 2 3 / 50%)` and mixes of both all work
+    let values: Vec<&str> = arguments
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|value| !value.is_empty())
+        .collect();
+    if !(3..=4).contains(&values.len()) {
+        return Err(format!("{}() takes 3 values and an optional alpha, got {}", function, values.len()));
+    }
+    let alpha = values.get(3).map(|value| number(value, 1.0).map(|a| (a.clamp(0.0, 1.0) * 255.0).round() as u8)).transpose()?;
+
+    let rgb = match function {
+        "rgb" | "rgba" => {
+            let channel = |value| number(value, 255.0).map(|c| c.clamp(0.0, 255.0).round() as u8);
+            (channel(values[0])?, channel(values[1])?, channel(values[2])?)
+        }
+        "hsl" | "hsla" => {
+            // Bare numbers are percentages in legacy syntax
+            let percent = |value: &str| number(value.trim_end_matches('%'), 1.0).map(|v| (v / 100.0).clamp(0.0, 1.0));
+            color::hsl_to_rgb(hue(values[0])?, percent(values[1])?, percent(values[2])?)
+        }
+        "oklch" => Oklch {
+            l: number(values[0], 1.0)?.clamp(0.0, 1.0),
+            c: number(values[1], 0.4)?.max(0.0),
+            h: hue(values[2])?,
+        }
+        .to_rgb(),
+        "oklab" => Oklab {
+            l: number(values[0], 1.0)?.clamp(0.0, 1.0),
+            a: number(values[1], 0.4)?,
+            b: number(values[2], 0.4)?,
+        }
+        .to_rgb(),
+        _ => return Err(format!("Unknown color function '{}()'", function)),
+    };
+
+    Ok(with_alpha(rgb, alpha))
+}
+
+/// A plain number, or a percentage of `full`
+fn number(value: &str, full: f32) -> Result<f32, String> {
+    let parsed = match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().map(|p| p / 100.0 * full),
+        None => value.parse::<f32>(),
+    };
+    parsed.map_err(|_| format!("'{}' is not a number", value))
+}
+
+/// An angle in degrees, also accepting `deg`, `rad`, `grad` and `turn` units
+fn hue(value: &str) -> Result<f32, String> {
+    let (number, scale) = [("deg", 1.0), ("grad", 0.9), ("rad", 180.0 / std::f32::consts::PI), ("turn", 360.0)]
+        .iter()
+        .find_map(|&(unit, scale)| value.strip_suffix(unit).map(|number| (number, scale)))
+        .unwrap_or((value, 1.0));
+
+    number
+        .parse::<f32>()
+        .map(|h| (h * scale).rem_euclid(360.0))
+        .map_err(|_| format!("'{}' is not an angle", value))
+}
+
+fn with_alpha(rgb: (u8, u8, u8), alpha: Option<u8>) -> ParsedColor {
+    ParsedColor { rgb, alpha: alpha.filter(|&a| a < 255) }
+}
+
+fn unpack(value: u32) -> (u8, u8, u8) {
+    ((value >> 16) as u8, (value >> 8) as u8, value as u8)
+}
+
+/// The CSS named colors
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xF0F8FF), ("antiquewhite", 0xFAEBD7), ("aqua", 0x00FFFF), ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF), ("beige", 0xF5F5DC), ("bisque", 0xFFE4C4), ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD), ("blue", 0x0000FF), ("blueviolet", 0x8A2BE2), ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887), ("cadetblue", 0x5F9EA0), ("chartreuse", 0x7FFF00), ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50), ("cornflowerblue", 0x6495ED), ("cornsilk", 0xFFF8DC), ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF), ("darkblue", 0x00008B), ("darkcyan", 0x008B8B), ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9), ("darkgreen", 0x006400), ("darkgrey", 0xA9A9A9), ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B), ("darkolivegreen", 0x556B2F), ("darkorange", 0xFF8C00), ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000), ("darksalmon", 0xE9967A), ("darkseagreen", 0x8FBC8F), ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F), ("darkslategrey", 0x2F4F4F), ("darkturquoise", 0x00CED1), ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493), ("deepskyblue", 0x00BFFF), ("dimgray", 0x696969), ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF), ("firebrick", 0xB22222), ("floralwhite", 0xFFFAF0), ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF), ("gainsboro", 0xDCDCDC), ("ghostwhite", 0xF8F8FF), ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520), ("gray", 0x808080), ("green", 0x008000), ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080), ("honeydew", 0xF0FFF0), ("hotpink", 0xFF69B4), ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082), ("ivory", 0xFFFFF0), ("khaki", 0xF0E68C), ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5), ("lawngreen", 0x7CFC00), ("lemonchiffon", 0xFFFACD), ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080), ("lightcyan", 0xE0FFFF), ("lightgoldenrodyellow", 0xFAFAD2), ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90), ("lightgrey", 0xD3D3D3), ("lightpink", 0xFFB6C1), ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA), ("lightskyblue", 0x87CEFA), ("lightslategray", 0x778899), ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE), ("lightyellow", 0xFFFFE0), ("lime", 0x00FF00), ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6), ("magenta", 0xFF00FF), ("maroon", 0x800000), ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD), ("mediumorchid", 0xBA55D3), ("mediumpurple", 0x9370DB), ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE), ("mediumspringgreen", 0x00FA9A), ("mediumturquoise", 0x48D1CC), ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970), ("mintcream", 0xF5FFFA), ("mistyrose", 0xFFE4E1), ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD), ("navy", 0x000080), ("oldlace", 0xFDF5E6), ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23), ("orange", 0xFFA500), ("orangered", 0xFF4500), ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA), ("palegreen", 0x98FB98), ("paleturquoise", 0xAFEEEE), ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5), ("peachpuff", 0xFFDAB9), ("peru", 0xCD853F), ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD), ("powderblue", 0xB0E0E6), ("purple", 0x800080), ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000), ("rosybrown", 0xBC8F8F), ("royalblue", 0x4169E1), ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072), ("sandybrown", 0xF4A460), ("seagreen", 0x2E8B57), ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D), ("silver", 0xC0C0C0), ("skyblue", 0x87CEEB), ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090), ("slategrey", 0x708090), ("snow", 0xFFFAFA), ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4), ("tan", 0xD2B48C), ("teal", 0x008080), ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347), ("turquoise", 0x40E0D0), ("violet", 0xEE82EE), ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF), ("whitesmoke", 0xF5F5F5), ("yellow", 0xFFFF00), ("yellowgreen", 0x9ACD32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(text: &str) -> (u8, u8, u8) {
+        parse(text).unwrap_or_else(|e| panic!("{}", e)).rgb
+    }
+
+    /// Channels within `tolerance` of each other, for notations that don't
+    /// land exactly on 8-bit values
+    fn assert_close(actual: (u8, u8, u8), expected: (u8, u8, u8), tolerance: u8) {
+        let within = |a: u8, b: u8| a.abs_diff(b) <= tolerance;
+        assert!(
+            within(actual.0, expected.0) && within(actual.1, expected.1) && within(actual.2, expected.2),
+            "{:?} is not within {} of {:?}",
+            actual,
+            tolerance,
+            expected,
+        );
+    }
+
+    #[test]
+    fn hex_forms() {
+        assert_eq!(parse("#f80"), Ok(ParsedColor::opaque((255, 136, 0))));
+        assert_eq!(parse("#f808"), Ok(ParsedColor { rgb: (255, 136, 0), alpha: Some(0x88) }));
+        assert_eq!(parse("#FF8800"), Ok(ParsedColor::opaque((255, 136, 0))));
+        assert_eq!(parse("ff880080"), Ok(ParsedColor { rgb: (255, 136, 0), alpha: Some(0x80) }));
+        // An opaque alpha is the same as none
+        assert_eq!(parse("#ff8800ff"), Ok(ParsedColor::opaque((255, 136, 0))));
+    }
+
+    #[test]
+    fn rgb_functions() {
+        assert_eq!(rgb("rgb(12, 34, 56)"), (12, 34, 56));
+        assert_eq!(rgb("RGB(12 34 56)"), (12, 34, 56));
+        assert_eq!(rgb("rgb(100%, 50%, 0%)"), (255, 128, 0));
+        assert_eq!(rgb("rgb(300, -5, 0)"), (255, 0, 0));
+        assert_eq!(parse("rgba(255, 0, 0, 0.5)").unwrap().alpha, Some(128));
+        assert_eq!(parse("rgb(255 0 0 / 25%)").unwrap().alpha, Some(64));
+    }
+
+    #[test]
+    fn hsl_functions() {
+        assert_eq!(rgb("hsl(120, 100%, 25%)"), (0, 128, 0));
+        assert_eq!(rgb("hsl(0.5turn 100% 50%)"), (0, 255, 255));
+        assert_eq!(rgb("hsl(-120deg, 100%, 50%)"), (0, 0, 255));
+        assert_eq!(parse("hsla(0, 0%, 100%, 0)").unwrap(), ParsedColor { rgb: (255, 255, 255), alpha: Some(0) });
+    }
+
+    #[test]
+    fn oklab_and_oklch_functions() {
+        // CSS Color 4's values for pure red
+        assert_close(rgb("oklch(62.8% 0.2577 29.23)"), (255, 0, 0), 1);
+        assert_close(rgb("oklch(0.628 0.2577 29.23deg)"), (255, 0, 0), 1);
+        assert_close(rgb("oklab(62.8% 0.2249 0.1258)"), (255, 0, 0), 1);
+        assert_eq!(rgb("oklch(100% 0 0)"), (255, 255, 255));
+        assert_eq!(parse("oklab(0 0 0 / 0.5)").unwrap(), ParsedColor { rgb: (0, 0, 0), alpha: Some(128) });
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_eq!(rgb("rebeccapurple"), (102, 51, 153));
+        assert_eq!(rgb(" Navy "), (0, 0, 128));
+        assert_eq!(parse("transparent").unwrap().alpha, Some(0));
+        assert_eq!(nearest_name((255, 0, 0)), ("red", true));
+        assert_eq!(nearest_name((250, 3, 2)), ("red", false));
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        for text in ["", "   ", "#12", "#12345", "#ggg", "notacolor", "rgb(1, 2)", "rgb(1, 2, 3, 4, 5)", "rgb(1, 2, 3", "rgb(a, b, c)", "hsl(x, 10%, 10%)", "cmyk(0, 0, 0)"] {
+            assert!(parse(text).is_err(), "'{}' parsed", text);
+        }
+    }
+
+    #[test]
+    fn formats_parse_back() {
+        let colors = [
+            ParsedColor::opaque((51, 102, 153)),
+            ParsedColor { rgb: (200, 30, 90), alpha: Some(128) },
+        ];
+
+        for color in colors {
+            assert_eq!(Format::Hex.format(color), hex_string(color.rgb, color.alpha));
+            for format in [Format::Hex, Format::Rgb, Format::Oklch, Format::Oklab] {
+                let parsed = parse(&format.format(color)).unwrap();
+                assert_close(parsed.rgb, color.rgb, 1);
+                assert_eq!(parsed.alpha, color.alpha, "{}", format.label());
+            }
+            // Whole percentages lose a little precision
+            assert_close(parse(&Format::Hsl.format(color)).unwrap().rgb, color.rgb, 3);
+        }
+
+        assert_eq!(Format::Rgb.format(ParsedColor { rgb: (1, 2, 3), alpha: Some(128) }), "rgba(1, 2, 3, 0.5)");
+        assert_eq!(Format::Name.format(ParsedColor::opaque((0, 0, 129))), "navy");
+    }
+
+    #[test]
+    fn format_names() {
+        assert_eq!("RGBA".parse::<Format>(), Ok(Format::Rgb));
+        assert_eq!("named".parse::<Format>(), Ok(Format::Name));
+        assert!("cmyk".parse::<Format>().is_err());
+        for format in Format::ALL {
+            assert_eq!(format.label().parse::<Format>(), Ok(format));
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use image::RgbaImage;
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::collections::HashMap;
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use image::RgbaImage;
+    use zbus::blocking::{Connection, MessageIterator};
+    use zbus::zvariant::Value;
+    use zbus::MatchRule;
+
+    const DESTINATION: &str = "org.freedesktop.Notifications";
+    const PATH: &str = "/org/freedesktop/Notifications";
+    const INTERFACE: &str = "org.freedesktop.Notifications";
+
+    /// A notification on screen, closed once we stop waiting for its actions
+    pub struct Notification {
+        conn: Connection,
+        id: u32,
+        timeout: Duration,
+        actions: mpsc::Receiver<String>,
+    }
+
+    impl Notification {
+        /// Blocks until one of the actions is clicked, the notification is
+        /// dismissed, or it times out
+        pub fn wait_for_action(&self) -> Option<String> {
+            match self.actions.recv_timeout(self.timeout + Duration::from_secs(1)) {
+                Ok(action) => Some(action),
+                Err(mpsc::RecvTimeoutError::Disconnected) => None,
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    // Servers that keep notifications around would otherwise
+                    // show buttons nobody is listening to anymore
+                    self.conn
+                        .call_method(Some(DESTINATION), PATH, Some(INTERFACE), "CloseNotification", &(self.id,))
+                        .ok();
+                    None
+                }
+            }
+        }
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+    }
+
+    pub fn send(summary: &str, body: &str, icon: &RgbaImage, actions: &[(&str, &str)], timeout: Duration) -> Result<Notification, String> {
+        let conn = Connection::session().map_err(|e| format!("Cannot connect to the session bus: {}", e))?;
+        send_on(conn, summary, body, icon, actions, timeout)
+    }
+
+    /// [`send`] over any connection, tests hand it a peer-to-peer one
+    pub fn send_on(conn: Connection, summary: &str, body: &str, icon: &RgbaImage, actions: &[(&str, &str)], timeout: Duration) -> Result<Notification, String> {
+        let reply = conn
+            .call_method(Some(DESTINATION), PATH, Some(INTERFACE), "GetCapabilities", &())
+            .map_err(|e| format!("No notification server is running: {}", e))?;
+        let capabilities: Vec<String> = reply.body().deserialize().map_err(|e| e.to_string())?;
+        let has = |capability: &str| capabilities.iter().any(|c| c == capability);
+
+        let actions: Vec<&str> = if has("actions") {
+            actions.iter().flat_map(|&(key, label)| [key, label]).collect()
+        } else {
+            Vec::new()
+        };
+        let body = if has("body-markup") { escape(body) } else { body.to_string() };
+
+        let (width, height) = icon.dimensions();
+        let image = Value::from((
+            width as i32,
+            height as i32,
+            width as i32 * 4,
+            true,
+            8i32,
+            4i32,
+            icon.as_raw().clone(),
+        ));
+        let hints = HashMap::from([("image-data", image)]);
+
+        // Subscribed before sending, a fast click could beat us otherwise
+        let rule = MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .interface(INTERFACE)
+            .and_then(|rule| rule.path(PATH))
+            .map_err(|e| e.to_string())?
+            .build();
+        let signals = MessageIterator::for_match_rule(rule, &conn, None)
+            .map_err(|e| format!("Failed to subscribe to notification actions: {}", e))?;
+
+        let reply = conn
+            .call_method(
+                Some(DESTINATION),
+                PATH,
+                Some(INTERFACE),
+                "Notify",
+                &("yoinkctl", 0u32, "", summary, body.as_str(), &actions, hints, timeout.as_millis() as i32),
+            )
+            .map_err(|e| format!("Notify failed: {}", e))?;
+        let id: u32 = reply.body().deserialize().map_err(|e| e.to_string())?;
+
+        let (sender, receiver) = mpsc::channel();
+        if !actions.is_empty() {
+            std::thread::spawn(move || {
+                for message in signals.flatten() {
+                    let header = message.header();
+                    let Some(member) = header.member() else {
+                        continue;
+                    };
+
+                    match member.as_str() {
+                        "ActionInvoked" => {
+                            if let Ok((notification, action)) = message.body().deserialize::<(u32, String)>() {
+                                if notification == id {
+                                    sender.send(action).ok();
+                                    return;
+                                }
+                            }
+                        }
+                        "NotificationClosed" => {
+                            if let Ok((notification, _reason)) = message.body().deserialize::<(u32, u32)>() {
+                                if notification == id {
+                                    return;
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            });
+        }
+
+        Ok(Notification { conn, id, timeout, actions: receiver })
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use std::time::Duration;
+    use image::RgbaImage;
+
+    pub struct Notification;
+
+    impl Notification {
+        pub fn wait_for_action(&self) -> Option<String> {
+            None
+        }
+    }
+
+    pub fn send(_summary: &str, _body: &str, _icon: &RgbaImage, _actions: &[(&str, &str)], _timeout: Duration) -> Result<Notification, String> {
+        Err("Desktop notifications are only available on Linux".to_string())
+    }
+}
+
+pub use imp::Notification;
+
+/// Shows a notification through `org.freedesktop.Notifications`.
+///
+/// `actions` are `(key, label)` pairs and are left out when the server can't
+/// show buttons. `timeout` is how long the server should keep it on screen.
+pub fn send(summary: &str, body: &str, icon: &RgbaImage, actions: &[(&str, &str)], timeout: Duration) -> Result<Notification, String> {
+    imp::send(summary, body, icon, actions, timeout)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::imp;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use image::{Rgba, RgbaImage};
+    use zbus::blocking::Connection;
+    use zbus::zvariant::{OwnedValue, Structure};
+    use zbus::{fdo, interface};
+
+    use crate::testing::peer_connection;
+
+    const PATH: &str = "/org/freedesktop/Notifications";
+    const INTERFACE: &str = "org.freedesktop.Notifications";
+    const ID: u32 = 7;
+
+    #[derive(Default)]
+    struct Received {
+        summary: String,
+        body: String,
+        actions: Vec<String>,
+        icon: Option<(i32, i32)>,
+        expire_timeout: i32,
+        closed: Vec<u32>,
+    }
+
+    /// A notification server that clicks `click` as soon as it's shown
+    struct Server {
+        capabilities: Vec<&'static str>,
+        click: Option<&'static str>,
+        received: Arc<Mutex<Received>>,
+    }
+
+    #[interface(name = "org.freedesktop.Notifications")]
+    impl Server {
+        fn get_capabilities(&self) -> Vec<&str> {
+            self.capabilities.clone()
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        async fn notify(
+            &self,
+            #[zbus(connection)] conn: &zbus::Connection,
+            _app_name: &str,
+            _replaces_id: u32,
+            _app_icon: &str,
+            summary: &str,
+            body: &str,
+            actions: Vec<String>,
+            hints: HashMap<String, OwnedValue>,
+            expire_timeout: i32,
+        ) -> fdo::Result<u32> {
+            let icon = hints.get("image-data")
+                .and_then(|value| Structure::try_from(value.try_clone().ok()?).ok())
+                .and_then(|image| match image.fields() {
+                    [width, height, ..] => Some((i32::try_from(width).ok()?, i32::try_from(height).ok()?)),
+                    _ => None,
+                });
+            *self.received.lock().unwrap() = Received {
+                summary: summary.to_string(),
+                body: body.to_string(),
+                actions,
+                icon,
+                expire_timeout,
+                closed: Vec::new(),
+            };
+
+            // Sent before the reply, like a click that beats the round trip
+            if let Some(action) = self.click {
+                conn.emit_signal(None::<()>, PATH, INTERFACE, "ActionInvoked", &(ID, action)).await?;
+                conn.emit_signal(None::<()>, PATH, INTERFACE, "NotificationClosed", &(ID, 2u32)).await?;
+            }
+            Ok(ID)
+        }
+
+        fn close_notification(&self, id: u32) {
+            self.received.lock().unwrap().closed.push(id);
+        }
+    }
+
+    fn connect(server: Server) -> (Connection, Connection) {
+        peer_connection(move |builder| builder.serve_at(PATH, server))
+    }
+
+    fn server(capabilities: Vec<&'static str>, click: Option<&'static str>) -> (Server, Arc<Mutex<Received>>) {
+        let received = Arc::new(Mutex::new(Received::default()));
+        (Server { capabilities, click, received: received.clone() }, received)
+    }
+
+    fn icon() -> RgbaImage {
+        RgbaImage::from_pixel(3, 2, Rgba([51, 102, 153, 255]))
+    }
+
+    const ACTIONS: [(&str, &str); 2] = [("rgb", "Copy as RGB"), ("history", "Open history")];
+
+    #[test]
+    fn clicked_action_is_returned() {
+        let (server, received) = server(vec!["actions", "body"], Some("rgb"));
+        let (conn, _peer) = connect(server);
+
+        let notification = imp::send_on(conn, "Copied #336699", "rgb(51, 102, 153)", &icon(), &ACTIONS, Duration::from_secs(6)).unwrap();
+        assert_eq!(notification.wait_for_action().as_deref(), Some("rgb"));
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.summary, "Copied #336699");
+        assert_eq!(received.body, "rgb(51, 102, 153)");
+        assert_eq!(received.actions, ["rgb", "Copy as RGB", "history", "Open history"]);
+        assert_eq!(received.icon, Some((3, 2)));
+        assert_eq!(received.expire_timeout, 6000);
+        assert!(received.closed.is_empty());
+    }
+
+    #[test]
+    fn servers_without_buttons_get_no_actions() {
+        let (server, received) = server(vec!["body"], None);
+        let (conn, _peer) = connect(server);
+
+        let notification = imp::send_on(conn, "Picked #336699", "a", &icon(), &ACTIONS, Duration::from_secs(6)).unwrap();
+        let waited = Instant::now();
+        assert_eq!(notification.wait_for_action(), None);
+        assert!(waited.elapsed() < Duration::from_secs(1), "nothing to wait for without actions");
+
+        assert!(received.lock().unwrap().actions.is_empty());
+    }
+
+    #[test]
+    fn markup_is_escaped_when_supported() {
+        let (server, received) = server(vec!["body", "body-markup"], None);
+        let (conn, _peer) = connect(server);
+
+        imp::send_on(conn, "Picked", "Couldn't copy: <X11> & more", &icon(), &[], Duration::from_secs(6)).unwrap();
+        assert_eq!(received.lock().unwrap().body, "Couldn't copy: &lt;X11&gt; &amp; more");
+    }
+
+    #[test]
+    fn unanswered_notification_is_closed_after_its_timeout() {
+        let (server, received) = server(vec!["actions", "body"], None);
+        let (conn, _peer) = connect(server);
+
+        let notification = imp::send_on(conn, "Copied", "", &icon(), &ACTIONS, Duration::from_millis(100)).unwrap();
+        assert_eq!(notification.wait_for_action(), None);
+        assert_eq!(received.lock().unwrap().closed, [ID]);
+    }
+}
@@ -0,0 +1,306 @@
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+use crate::color::{self, Oklab};
+
+/// One dominant color and the share of the sampled pixels it stands for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaletteColor {
+    pub hex: String,
+    pub rgb: (u8, u8, u8),
+    /// Fraction of pixels, 0..1
+    pub coverage: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// k-means clustering in OKLab, groups colors the way they look
+    KMeans,
+    /// Recursive median cut in RGB, faster and fully deterministic
+    MedianCut,
+}
+
+impl std::str::FromStr for Method {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "kmeans" | "k-means" => Ok(Method::KMeans),
+            "median-cut" | "mediancut" | "median" => Ok(Method::MedianCut),
+            _ => Err(format!("Unknown palette method '{}' (use kmeans or median-cut)", s)),
+        }
+    }
+}
+
+/// Pixels beyond this are subsampled, a few tens of thousands are plenty
+const MAX_SAMPLES: usize = 50_000;
+const KMEANS_ITERATIONS: usize = 24;
+
+/// Quantizes `image` into at most `count` colors, most common first.
+///
+/// Mostly transparent pixels are ignored, so cut-out images give the palette
+/// of the subject rather than of the background.
+pub fn extract(image: &RgbaImage, count: usize, method: Method) -> Vec<PaletteColor> {
+    let pixels = sample(image);
+    if pixels.is_empty() || count == 0 {
+        return Vec::new();
+    }
+
+    let mut clusters = match method {
+        Method::KMeans => kmeans(&pixels, count),
+        Method::MedianCut => median_cut(pixels.clone(), count),
+    };
+
+    clusters.retain(|(_, size)| *size > 0);
+    clusters.sort_by_key(|&(_, size)| std::cmp::Reverse(size));
+
+    clusters
+        .into_iter()
+        .map(|(rgb, size)| PaletteColor {
+            hex: color::hex_string(rgb, None),
+            rgb,
+            coverage: size as f32 / pixels.len() as f32,
+        })
+        .collect()
+}
+
+fn sample(image: &RgbaImage) -> Vec<(u8, u8, u8)> {
+    let total = image.width() as usize * image.height() as usize;
+    let step = total.div_ceil(MAX_SAMPLES).max(1);
+
+    image
+        .pixels()
+        .step_by(step)
+        .filter(|pixel| pixel[3] >= 128)
+        .map(|pixel| (pixel[0], pixel[1], pixel[2]))
+        .collect()
+}
+
+/// Lloyd's algorithm seeded with k-means++, returning each centroid and its size
+fn kmeans(pixels: &[(u8, u8, u8)], count: usize) -> Vec<((u8, u8, u8), usize)> {
+    let points: Vec<Oklab> = pixels.iter().map(|&rgb| Oklab::from_rgb(rgb)).collect();
+    let mut rng = Lcg(0x9E37_79B9_7F4A_7C15);
+
+    // k-means++: each new seed is picked with probability proportional to its
+    // squared distance from the nearest existing one
+    let mut centroids = vec![points[rng.below(points.len())]];
+    let mut nearest: Vec<f32> = points.iter().map(|p| p.distance_squared(centroids[0])).collect();
+    while centroids.len() < count {
+        let total: f32 = nearest.iter().sum();
+        if total <= f32::EPSILON {
+            // Fewer distinct colors than requested
+            break;
+        }
+
+        let mut target = rng.unit() * total;
+        let index = nearest
+            .iter()
+            .position(|&d| {
+                target -= d;
+                target <= 0.0
+            })
+            .unwrap_or(points.len() - 1);
+        centroids.push(points[index]);
+
+        for (distance, point) in nearest.iter_mut().zip(&points) {
+            *distance = distance.min(point.distance_squared(points[index]));
+        }
+    }
+
+    let mut assignment = vec![0; points.len()];
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut changed = false;
+        for (slot, point) in assignment.iter_mut().zip(&points) {
+            let closest = closest(&centroids, *point);
+            if *slot != closest {
+                *slot = closest;
+                changed = true;
+            }
+        }
+
+        let mut sums = vec![(Oklab::default(), 0usize); centroids.len()];
+        for (&cluster, point) in assignment.iter().zip(&points) {
+            let (sum, size) = &mut sums[cluster];
+            sum.l += point.l;
+            sum.a += point.a;
+            sum.b += point.b;
+            *size += 1;
+        }
+        for (centroid, (sum, size)) in centroids.iter_mut().zip(&sums) {
+            if *size > 0 {
+                let n = *size as f32;
+                *centroid = Oklab { l: sum.l / n, a: sum.a / n, b: sum.b / n };
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let mut sizes = vec![0; centroids.len()];
+    for &cluster in &assignment {
+        sizes[cluster] += 1;
+    }
+
+    centroids.into_iter().map(Oklab::to_rgb).zip(sizes).collect()
+}
+
+fn closest(centroids: &[Oklab], point: Oklab) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, c.distance_squared(point)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// Splits the box with the widest channel range at its median until there
+/// are `count` boxes, each standing for its mean color
+fn median_cut(pixels: Vec<(u8, u8, u8)>, count: usize) -> Vec<((u8, u8, u8), usize)> {
+    let channel = |rgb: &(u8, u8, u8), c: usize| [rgb.0, rgb.1, rgb.2][c];
+    let widest = |pixels: &[(u8, u8, u8)]| -> (usize, u8) {
+        (0..3)
+            .map(|c| {
+                let (min, max) = pixels.iter().fold((255, 0), |(min, max), rgb| {
+                    (channel(rgb, c).min(min), channel(rgb, c).max(max))
+                });
+                (c, max.saturating_sub(min))
+            })
+            .max_by_key(|&(_, range)| range)
+            .unwrap_or((0, 0))
+    };
+
+    let mut boxes = vec![pixels];
+    while boxes.len() < count {
+        // Prefer splitting big, spread-out boxes
+        let Some((index, c)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| {
+                let (c, range) = widest(b);
+                (i, c, range as usize * b.len())
+            })
+            .filter(|&(_, _, score)| score > 0)
+            .max_by_key(|&(_, _, score)| score)
+            .map(|(i, c, _)| (i, c))
+        else {
+            break;
+        };
+
+        let mut group = boxes.swap_remove(index);
+        group.sort_unstable_by_key(|rgb| channel(rgb, c));
+        // Cut at the median value rather than the median index, so pixels of
+        // one color never end up averaged into two boxes
+        let median = channel(&group[group.len() / 2], c);
+        let mut split = group.partition_point(|rgb| channel(rgb, c) < median);
+        if split == 0 {
+            split = group.partition_point(|rgb| channel(rgb, c) <= median);
+        }
+        let upper = group.split_off(split);
+        boxes.push(group);
+        boxes.push(upper);
+    }
+
+    boxes
+        .into_iter()
+        .map(|group| {
+            let n = group.len().max(1) as u64;
+            let (r, g, b) = group.iter().fold((0u64, 0u64, 0u64), |(r, g, b), rgb| {
+                (r + rgb.0 as u64, g + rgb.1 as u64, b + rgb.2 as u64)
+            });
+            (((r / n) as u8, (g / n) as u8, (b / n) as u8), group.len())
+        })
+        .collect()
+}
+
+/// Small fixed-seed generator so the same image always yields the same palette
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        self.0 >> 33
+    }
+
+    fn unit(&mut self) -> f32 {
+        self.next() as f32 / (1u64 << 31) as f32
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        self.next() as usize % n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const RED: (u8, u8, u8) = (230, 30, 40);
+    const BLUE: (u8, u8, u8) = (20, 60, 200);
+
+    /// 6 rows of red over 4 of blue, with a transparent green column that
+    /// must not count
+    fn two_colors() -> RgbaImage {
+        RgbaImage::from_fn(10, 10, |x, y| match (x, y) {
+            (9, _) => Rgba([0, 255, 0, 20]),
+            (_, 0..=5) => Rgba([RED.0, RED.1, RED.2, 255]),
+            _ => Rgba([BLUE.0, BLUE.1, BLUE.2, 255]),
+        })
+    }
+
+    fn assert_near(actual: (u8, u8, u8), expected: (u8, u8, u8)) {
+        let near = |a: u8, b: u8| a.abs_diff(b) <= 1;
+        assert!(
+            near(actual.0, expected.0) && near(actual.1, expected.1) && near(actual.2, expected.2),
+            "{:?} is not {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn two_colors_make_two_clusters() {
+        for method in [Method::KMeans, Method::MedianCut] {
+            // Asking for more than there are gives just the two
+            for count in [2, 5] {
+                let palette = extract(&two_colors(), count, method);
+                assert_eq!(palette.len(), 2, "{:?} with {}", method, count);
+
+                assert_near(palette[0].rgb, RED);
+                assert_near(palette[1].rgb, BLUE);
+                assert_eq!(palette[0].hex, color::hex_string(palette[0].rgb, None));
+                // 54 red and 36 blue pixels once the transparent column is dropped
+                assert!((palette[0].coverage - 0.6).abs() < 1e-6, "{:?}", method);
+                assert!((palette[1].coverage - 0.4).abs() < 1e-6, "{:?}", method);
+            }
+        }
+    }
+
+    #[test]
+    fn transparent_images_have_no_palette() {
+        let image = RgbaImage::from_pixel(4, 4, Rgba([255, 0, 0, 127]));
+        assert!(extract(&image, 3, Method::KMeans).is_empty());
+        assert!(extract(&image, 3, Method::MedianCut).is_empty());
+        assert!(extract(&two_colors(), 0, Method::KMeans).is_empty());
+    }
+
+    #[test]
+    fn one_cluster_averages_everything() {
+        let palette = extract(&two_colors(), 1, Method::MedianCut);
+        assert_eq!(palette.len(), 1);
+        assert_eq!(palette[0].coverage, 1.0);
+        // Mean of 54 red and 36 blue pixels
+        assert_eq!(palette[0].rgb, (146, 42, 104));
+    }
+
+    #[test]
+    fn method_names() {
+        assert_eq!("kmeans".parse::<Method>(), Ok(Method::KMeans));
+        assert_eq!("median-cut".parse::<Method>(), Ok(Method::MedianCut));
+        assert!("octree".parse::<Method>().is_err());
+    }
+}
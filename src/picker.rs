@@ -1,105 +1,597 @@
 use eframe::egui;
-use xcap::Monitor;
 use image::RgbaImage;
-use arboard::Clipboard;
-use crate::config::Config;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use crate::capture::{self, Capture};
+use crate::clipboard;
+use crate::color::{alpha_fraction, hex_string, rgb_to_hsl, Cvd};
+use crate::config::{Config, OverlayMode};
 use crate::history::ColorHistory;
+use crate::icc::{ColorManagement, ManagedSpace};
+use crate::notation::{Format, ParsedColor};
+use crate::notify;
+use crate::portal;
+use crate::theme::Palette;
 
 pub struct ColorPicker {
     screenshot: Option<RgbaImage>,
     screenshot_offset: (i32, i32),
+    screenshot_scale: f32,
+    /// Refreshed every frame, the window can move between monitors
+    mapping: ScreenMapping,
+    /// The screenshot has a meaningful alpha channel, e.g. a transparent PNG
+    screenshot_alpha: bool,
+    /// ICC conversions for calibrated monitors, only for screen captures
+    color_management: Option<ColorManagement>,
+    /// Conversions still loading on a background thread
+    color_management_loading: Option<mpsc::Receiver<Option<ColorManagement>>>,
+    /// What `color_management` was loaded for, the resident picker keeps it
+    /// across picks while this stays the same
+    color_management_key: Option<ColorManagementKey>,
     cursor_pos: egui::Pos2,
     magnifier_pos: egui::Pos2,
     magnifier_offset: egui::Vec2,
     should_close: bool,
     config: Config,
     initialized: bool,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    /// Set when picking from an image file: the image is drawn in the window
+    /// instead of sampling the screen behind a transparent overlay
+    view: Option<ImageView>,
+    /// Where the primary button went down, once it turns into a drag this is
+    /// a region selection instead of a pick
+    drag_start: Option<egui::Pos2>,
+    selecting: bool,
+    /// Ruler mode, toggled with `M`: drags measure distances instead of selecting
+    measuring: bool,
+    /// Last finished measurement as screenshot pixel coordinates
+    measurement: Option<((i32, i32), (i32, i32))>,
+    /// Color vision deficiency simulation, cycled with `V`
+    simulation: Option<Cvd>,
+    /// Simulate the whole frozen screenshot too, not just the magnifier (`Shift+V`)
+    simulate_screen: bool,
+    simulated_texture: Option<(Cvd, egui::TextureHandle)>,
+    /// Screenshot painted behind everything in freeze mode, built on first use
+    background: Option<egui::TextureHandle>,
+    /// Keeps re-capturing the area around the cursor in live mode
+    live: Option<LiveCapture>,
+    /// Live capture isn't possible here (e.g. the portal), the overlay froze instead
+    live_failed: bool,
+    /// Where the magnifier and its info box were drawn last frame, shadows
+    /// aside. Live captures wait while they'd see either.
+    magnifier_rects: [egui::Rect; 2],
+    /// Overlay position the magnifier was pinned at with `Space`, the pointer
+    /// is then free to hover its cells
+    pinned: Option<egui::Pos2>,
+    /// Top-left corner and cell size of the magnifier grid as last drawn
+    magnifier_cells: Option<(egui::Pos2, f32)>,
+    /// Colors for the configured theme, refreshed every frame
+    palette: Palette,
 }
 
-impl ColorPicker {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let (screenshot, offset) = capture_all_screens();
+/// Settings and desktop layout that ICC conversions depend on
+#[derive(PartialEq)]
+struct ColorManagementKey {
+    managed_space: ManagedSpace,
+    icc_profile: Option<PathBuf>,
+    monitor_icc_profiles: BTreeMap<String, PathBuf>,
+    /// Capture origin and size, which change with the monitor layout
+    desktop: ((i32, i32), (u32, u32)),
+}
+
+/// Magnifier cells reach this many pixels out from the center one
+const MAGNIFIER_RADIUS: i32 = 5;
+
+/// Half the side of the area live mode re-captures, in desktop units. A bit
+/// more than the magnifier shows, so edge snapping has fresh pixels too.
+const LIVE_RADIUS: i32 = 16;
+const LIVE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Whether any of `drawn` lies in the area a live capture around `center`
+/// reads, with a little slack. Our own drawing would end up in the capture.
+fn covers_live_area(drawn: &[egui::Rect], center: egui::Pos2, units_per_point: f32) -> bool {
+    let area = egui::Rect::from_center_size(
+        center,
+        egui::Vec2::splat((LIVE_RADIUS * 2 + 1) as f32 / units_per_point + 4.0),
+    );
+    drawn.iter().any(|rect| rect.intersects(area))
+}
+
+/// Region captures on their own thread, so a slow backend never stalls the overlay
+struct LiveCapture {
+    requests: mpsc::Sender<(i32, i32, u32, u32)>,
+    results: mpsc::Receiver<Result<Capture, String>>,
+    pending: bool,
+    last_request: Instant,
+}
+
+impl LiveCapture {
+    fn start() -> Self {
+        let (requests, regions) = mpsc::channel();
+        let (sender, results) = mpsc::channel();
+        
+        // Ends once the picker drops its sender
+        std::thread::spawn(move || {
+            for region in regions {
+                if sender.send(capture::capture_region(region)).is_err() {
+                    break;
+                }
+            }
+        });
         
         Self {
-            screenshot,
-            screenshot_offset: offset,
-            cursor_pos: egui::Pos2::ZERO,
-            magnifier_pos: egui::Pos2::ZERO,
-            magnifier_offset: egui::vec2(30.0, 30.0),
-            should_close: false,
-            config: Config::load().unwrap_or_default(),
-            initialized: false,
+            requests,
+            results,
+            pending: false,
+            last_request: Instant::now(),
         }
     }
-    
-    // OPTIMIZED: Accept pre-captured screenshot to avoid duplicate capture
-    pub fn new_with_screenshot(_cc: &eframe::CreationContext<'_>, screenshot: Option<RgbaImage>, offset: (i32, i32)) -> Self {
+}
+
+/// How overlay points line up with screenshot pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScreenMapping {
+    /// Desktop position of the window's top-left corner
+    window_origin: egui::Pos2,
+    /// Desktop units per egui point
+    units_per_point: f32,
+    /// Desktop position of the screenshot's top-left pixel
+    capture_offset: (i32, i32),
+    /// Screenshot pixels per desktop unit
+    capture_scale: f32,
+}
+
+impl ScreenMapping {
+    fn new(capture_offset: (i32, i32), capture_scale: f32) -> Self {
         Self {
-            screenshot,
-            screenshot_offset: offset,
-            cursor_pos: egui::Pos2::ZERO,
-            magnifier_pos: egui::Pos2::ZERO,
-            magnifier_offset: egui::vec2(30.0, 30.0),
-            should_close: false,
-            config: Config::load().unwrap_or_default(),
-            initialized: false,
+            // Until the window reports where it is, assume it covers the capture
+            window_origin: egui::pos2(capture_offset.0 as f32, capture_offset.1 as f32),
+            units_per_point: 1.0,
+            capture_offset,
+            capture_scale,
+        }
+    }
+    
+    /// Follows the window's position and scale factor.
+    ///
+    /// Desktop units are physical pixels on X11 and Windows, where every
+    /// monitor can have its own scale factor, and points on macOS.
+    fn update(&mut self, ctx: &egui::Context) {
+        let pixels_per_point = ctx.pixels_per_point();
+        self.units_per_point = if cfg!(target_os = "macos") {
+            pixels_per_point / ctx.native_pixels_per_point().unwrap_or(pixels_per_point)
+        } else {
+            pixels_per_point
+        };
+        
+        // Wayland doesn't tell windows their position, keep the fallback there
+        if let Some(inner) = ctx.input(|i| i.viewport().inner_rect) {
+            self.window_origin = (inner.min.to_vec2() * self.units_per_point).to_pos2();
         }
     }
     
+    fn to_pixel(self, pos: egui::Pos2) -> (i32, i32) {
+        let desktop = self.window_origin + pos.to_vec2() * self.units_per_point;
+        (
+            floor_pixel((desktop.x - self.capture_offset.0 as f32) * self.capture_scale),
+            floor_pixel((desktop.y - self.capture_offset.1 as f32) * self.capture_scale),
+        )
+    }
+    
+    /// Desktop position of a screenshot pixel
+    fn to_desktop(self, (x, y): (i32, i32)) -> (i32, i32) {
+        (
+            (x as f32 / self.capture_scale).floor() as i32 + self.capture_offset.0,
+            (y as f32 / self.capture_scale).floor() as i32 + self.capture_offset.1,
+        )
+    }
+    
+    /// Top-left corner of a screenshot pixel in window points
+    fn to_pos(self, (x, y): (i32, i32)) -> egui::Pos2 {
+        let desktop = egui::pos2(
+            x as f32 / self.capture_scale + self.capture_offset.0 as f32,
+            y as f32 / self.capture_scale + self.capture_offset.1 as f32,
+        );
+        ((desktop - self.window_origin) / self.units_per_point).to_pos2()
+    }
+}
+
+/// Floors a pixel coordinate, letting a pixel corner from [`ScreenMapping::to_pos`]
+/// that fractional scaling left a hair short still land in that pixel
+fn floor_pixel(value: f32) -> i32 {
+    (value + 0.01).floor() as i32
+}
+
+/// Pointer travel before a press counts as a region drag rather than a click
+const DRAG_THRESHOLD: f32 = 4.0;
+/// How far (in pixels) a ruler end looks for a color edge to snap to
+const SNAP_RADIUS: i32 = 8;
+/// Summed RGB difference between neighbours that counts as an edge
+const EDGE_THRESHOLD: i32 = 48;
+
+struct ImageView {
+    texture: egui::TextureHandle,
+    /// Window points per image pixel
+    zoom: f32,
+    /// Window position of the image's top-left corner
+    pan: egui::Vec2,
+    fitted: bool,
+}
+
+impl ImageView {
+    const MIN_ZOOM: f32 = 0.05;
+    const MAX_ZOOM: f32 = 64.0;
+    
+    fn zoom_at(&mut self, anchor: egui::Pos2, factor: f32) {
+        let zoom = (self.zoom * factor).clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+        // Keep the pixel under `anchor` in place
+        self.pan = anchor.to_vec2() - (anchor.to_vec2() - self.pan) * (zoom / self.zoom);
+        self.zoom = zoom;
+    }
+    
+    fn fit(&mut self, image_size: egui::Vec2, area: egui::Rect, max_zoom: f32) {
+        self.zoom = (area.width() / image_size.x)
+            .min(area.height() / image_size.y)
+            .min(max_zoom);
+        self.pan = area.center().to_vec2() - image_size * self.zoom / 2.0;
+    }
+}
+
+impl ColorPicker {
     // OPTIMIZED: Accept pre-loaded config AND screenshot for fastest startup
-    pub fn new_with_config(_cc: &eframe::CreationContext<'_>, screenshot: Option<RgbaImage>, offset: (i32, i32), config: Config) -> Self {
+    pub fn new_with_config(_cc: &eframe::CreationContext<'_>, capture: Option<Capture>, config: Config) -> Self {
+        let mut picker = Self::unmanaged(capture, config);
+        picker.load_color_management();
+        picker
+    }
+    
+    /// A picker reporting raw values, until `load_color_management`
+    fn unmanaged(capture: Option<Capture>, config: Config) -> Self {
+        let (screenshot, screenshot_offset, screenshot_scale, screenshot_alpha) = match capture {
+            Some(capture) => (Some(capture.image), capture.offset, capture.scale, capture.alpha),
+            None => (None, (0, 0), 1.0, false),
+        };
+        let palette = Palette::new(&config.theme, true);
+        
         Self {
+            color_management: None,
+            color_management_loading: None,
+            color_management_key: None,
             screenshot,
-            screenshot_offset: offset,
+            screenshot_offset,
+            screenshot_scale,
+            mapping: ScreenMapping::new(screenshot_offset, screenshot_scale),
+            screenshot_alpha,
             cursor_pos: egui::Pos2::ZERO,
             magnifier_pos: egui::Pos2::ZERO,
             magnifier_offset: egui::vec2(30.0, 30.0),
             should_close: false,
             config,
             initialized: false,
+            cancel_flag: None,
+            view: None,
+            drag_start: None,
+            selecting: false,
+            measuring: false,
+            measurement: None,
+            simulation: None,
+            simulate_screen: false,
+            simulated_texture: None,
+            background: None,
+            live: None,
+            live_failed: false,
+            magnifier_rects: [egui::Rect::NOTHING; 2],
+            pinned: None,
+            magnifier_cells: None,
+            palette,
+        }
+    }
+    
+    /// A picker for an image file, shown in a regular window with pan and zoom
+    pub fn new_for_image(cc: &eframe::CreationContext<'_>, capture: Capture, config: Config) -> Self {
+        let texture = load_texture(&cc.egui_ctx, "picked-image", &capture.image);
+        
+        // Image files aren't in the monitor's color space
+        let mut picker = Self::unmanaged(Some(capture), config);
+        picker.view = Some(ImageView {
+            texture,
+            zoom: 1.0,
+            pan: egui::Vec2::ZERO,
+            fitted: false,
+        });
+        picker
+    }
+    
+    /// Starts a fresh pick on an existing picker, used by the resident picker
+    /// that keeps its window around between hotkey presses
+    pub fn reset(&mut self, capture: Option<Capture>, config: Config) {
+        (self.screenshot, self.screenshot_offset, self.screenshot_scale, self.screenshot_alpha) = match capture {
+            Some(capture) => (Some(capture.image), capture.offset, capture.scale, capture.alpha),
+            None => (None, (0, 0), 1.0, false),
+        };
+        self.mapping = ScreenMapping::new(self.screenshot_offset, self.screenshot_scale);
+        self.magnifier_offset = egui::vec2(30.0, 30.0);
+        self.should_close = false;
+        self.config = config;
+        self.load_color_management();
+        self.initialized = false;
+        self.drag_start = None;
+        self.selecting = false;
+        self.measuring = false;
+        self.measurement = None;
+        self.simulation = None;
+        self.simulate_screen = false;
+        self.simulated_texture = None;
+        self.background = None;
+        self.live = None;
+        self.live_failed = false;
+        self.magnifier_rects = [egui::Rect::NOTHING; 2];
+        self.pinned = None;
+        self.magnifier_cells = None;
+    }
+    
+    /// Loads ICC conversions for the current capture on a background thread,
+    /// it opens an X connection and reads every monitor's profile. Reuses the
+    /// ones already loaded when nothing they depend on changed.
+    fn load_color_management(&mut self) {
+        let key = self.screenshot.as_ref().map(|screenshot| ColorManagementKey {
+            managed_space: self.config.managed_space,
+            icc_profile: self.config.icc_profile.clone(),
+            monitor_icc_profiles: self.config.monitor_icc_profiles.clone(),
+            desktop: (self.screenshot_offset, screenshot.dimensions()),
+        });
+        if key.is_some() && key == self.color_management_key {
+            return;
+        }
+        
+        self.color_management = None;
+        self.color_management_loading = None;
+        self.color_management_key = key;
+        if self.color_management_key.is_none() {
+            return;
+        }
+        
+        let (sender, receiver) = mpsc::channel();
+        let config = self.config.clone();
+        std::thread::spawn(move || {
+            sender.send(ColorManagement::load(&config)).ok();
+        });
+        self.color_management_loading = Some(receiver);
+    }
+    
+    /// Takes the loaded ICC conversions once they're ready, `wait` blocks
+    /// until then so a pick never reports raw values by accident
+    fn receive_color_management(&mut self, wait: bool) {
+        let Some(receiver) = &self.color_management_loading else {
+            return;
+        };
+        let loaded = if wait {
+            receiver.recv().map_err(|_| mpsc::TryRecvError::Disconnected)
+        } else {
+            receiver.try_recv()
+        };
+        
+        match loaded {
+            Ok(color_management) => self.color_management = color_management,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {}
+        }
+        self.color_management_loading = None;
+    }
+    
+    /// Closes the picker on its next frame when `flag` gets set, e.g. from a signal handler
+    pub fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.cancel_flag = Some(flag);
+    }
+    
+    pub fn cancel(&mut self) {
+        self.should_close = true;
+    }
+    
+    /// Monospace font at the configured text size
+    fn font(&self, size: f32) -> egui::FontId {
+        egui::FontId::monospace(size * self.config.theme.font_scale)
+    }
+    
+    /// Screenshot (or image) pixel being sampled, may be out of bounds: the
+    /// one under the cursor, or the hovered cell of a pinned magnifier
+    #[inline]
+    fn pixel_under_cursor(&self) -> (i32, i32) {
+        let (x, y) = self.magnifier_center();
+        let (dx, dy) = self.hovered_cell().unwrap_or((0, 0));
+        (x + dx, y + dy)
+    }
+    
+    /// Pixel in the middle of the magnifier
+    #[inline]
+    fn magnifier_center(&self) -> (i32, i32) {
+        self.pixel_at(self.pinned.unwrap_or(self.cursor_pos))
+    }
+    
+    /// Offset from the center of the pinned magnifier's cell under the pointer
+    fn hovered_cell(&self) -> Option<(i32, i32)> {
+        self.pinned?;
+        let (origin, size) = self.magnifier_cells?;
+        let cell = (self.cursor_pos - origin) / size;
+        let (x, y) = (cell.x.floor() as i32, cell.y.floor() as i32);
+        let side = MAGNIFIER_RADIUS * 2 + 1;
+        ((0..side).contains(&x) && (0..side).contains(&y)).then_some((x - MAGNIFIER_RADIUS, y - MAGNIFIER_RADIUS))
+    }
+    
+    #[inline]
+    fn pixel_at(&self, pos: egui::Pos2) -> (i32, i32) {
+        match &self.view {
+            Some(view) => {
+                let pos = (pos.to_vec2() - view.pan) / view.zoom;
+                (pos.x.floor() as i32, pos.y.floor() as i32)
+            }
+            None => self.mapping.to_pixel(pos),
+        }
+    }
+    
+    /// Inverse of [`Self::pixel_at`]: where the top-left corner of a pixel is on screen
+    #[inline]
+    fn pos_of_pixel(&self, (x, y): (i32, i32)) -> egui::Pos2 {
+        match &self.view {
+            Some(view) => (egui::vec2(x as f32, y as f32) * view.zoom + view.pan).to_pos2(),
+            None => self.mapping.to_pos((x, y)),
         }
     }
     
+    /// Ruler ends for the current drag, snapped to nearby color edges along
+    /// each axis the drag extends in unless `snap` is off
+    fn measure_points(&self, snap: bool) -> Option<((i32, i32), (i32, i32))> {
+        let mut start = self.pixel_at(self.drag_start?);
+        let mut end = self.pixel_under_cursor();
+        
+        if snap {
+            if (end.0 - start.0).abs() > SNAP_RADIUS {
+                start.0 = self.snap_to_edge(start, true);
+                end.0 = self.snap_to_edge(end, true);
+            }
+            if (end.1 - start.1).abs() > SNAP_RADIUS {
+                start.1 = self.snap_to_edge(start, false);
+                end.1 = self.snap_to_edge(end, false);
+            }
+        }
+        
+        Some((start, end))
+    }
+    
+    /// Nearest coordinate along one axis where the screenshot color changes
+    /// sharply, i.e. the edge between two pixels, or the original coordinate
+    fn snap_to_edge(&self, (x, y): (i32, i32), horizontal: bool) -> i32 {
+        let origin = if horizontal { x } else { y };
+        let Some(screenshot) = &self.screenshot else {
+            return origin;
+        };
+        
+        let color_at = |i: i32| {
+            let (px, py) = if horizontal { (i, y) } else { (x, i) };
+            if px < 0 || py < 0 || px as u32 >= screenshot.width() || py as u32 >= screenshot.height() {
+                return None;
+            }
+            Some(screenshot.get_pixel(px as u32, py as u32).0)
+        };
+        let is_edge = |i: i32| match (color_at(i - 1), color_at(i)) {
+            (Some(a), Some(b)) => (0..3).map(|c| (a[c] as i32 - b[c] as i32).abs()).sum::<i32>() > EDGE_THRESHOLD,
+            _ => false,
+        };
+        
+        (0..=SNAP_RADIUS)
+            .flat_map(|d| [origin + d, origin - d])
+            .find(|&i| is_edge(i))
+            .unwrap_or(origin)
+    }
+    
+    fn current_measurement(&self, ctx: &egui::Context) -> Option<((i32, i32), (i32, i32))> {
+        if self.measuring && self.selecting {
+            // Alt turns snapping off for free measuring
+            self.measure_points(!ctx.input(|i| i.modifiers.alt))
+        } else {
+            self.measurement
+        }
+    }
+    
+    fn copy_measurement(&self, (start, end): ((i32, i32), (i32, i32))) {
+        let text = measurement_text(start, end);
+        let primary = self.config.copy_to_primary;
+        track(std::thread::spawn(move || {
+            match clipboard::copy_text(&text, primary) {
+                Ok(()) => println!("📏 Copied {}", text),
+                Err(e) => eprintln!("❌ Failed to copy measurement: {}", e),
+            }
+        }));
+    }
+    
+    /// Screenshot pixels covered by the current drag, clamped to the image
+    /// as `(x, y, width, height)`
+    fn selection_pixels(&self) -> Option<(u32, u32, u32, u32)> {
+        let screenshot = self.screenshot.as_ref()?;
+        let start = self.pixel_at(self.drag_start?);
+        let end = self.pixel_under_cursor();
+        
+        let clamp_x = |x: i32| x.clamp(0, screenshot.width() as i32 - 1) as u32;
+        let clamp_y = |y: i32| y.clamp(0, screenshot.height() as i32 - 1) as u32;
+        let (left, right) = (clamp_x(start.0.min(end.0)), clamp_x(start.0.max(end.0)));
+        let (top, bottom) = (clamp_y(start.1.min(end.1)), clamp_y(start.1.max(end.1)));
+        
+        Some((left, top, right - left + 1, bottom - top + 1))
+    }
+    
+    /// Copies the selected crop as an image, or saves it as a PNG when `save` is set
+    fn finish_selection(&self, save: bool) {
+        let (Some(screenshot), Some((x, y, width, height))) = (&self.screenshot, self.selection_pixels()) else {
+            return;
+        };
+        let crop = image::imageops::crop_imm(screenshot, x, y, width, height).to_image();
+        
+        track(std::thread::spawn(move || {
+            let result = if save { save_crop(&crop) } else { copy_crop(crop) };
+            match result {
+                Ok(message) => println!("✅ {}", message),
+                Err(e) => eprintln!("❌ {}", e),
+            }
+        }));
+    }
+    
+    /// The color to report, converted through the monitor's ICC profile when it has one
     #[inline]
     fn get_color_at_cursor(&self) -> Option<egui::Color32> {
+        let raw = self.raw_color_at_cursor()?;
+        Some(self.managed_at_cursor().map_or(raw, |(_, managed)| managed))
+    }
+    
+    /// Raw and managed color under the cursor, when color management applies there
+    fn managed_at_cursor(&self) -> Option<(egui::Color32, egui::Color32)> {
+        let color_management = self.color_management.as_ref()?;
+        let raw = self.raw_color_at_cursor()?;
+        let desktop = self.mapping.to_desktop(self.pixel_under_cursor());
+        
+        let (r, g, b) = color_management.convert(desktop, (raw.r(), raw.g(), raw.b()))?;
+        Some((raw, egui::Color32::from_rgb(r, g, b)))
+    }
+    
+    /// Framebuffer bytes under the cursor
+    #[inline]
+    fn raw_color_at_cursor(&self) -> Option<egui::Color32> {
         let screenshot = self.screenshot.as_ref()?;
         
-        let x = (self.cursor_pos.x as i32 + self.screenshot_offset.0).max(0) as u32;
-        let y = (self.cursor_pos.y as i32 + self.screenshot_offset.1).max(0) as u32;
+        let (x, y) = self.pixel_under_cursor();
         
-        if x >= screenshot.width() || y >= screenshot.height() {
+        if x < 0 || y < 0 || x as u32 >= screenshot.width() || y as u32 >= screenshot.height() {
             return None;
         }
         
-        let pixel = screenshot.get_pixel(x, y);
-        Some(egui::Color32::from_rgba_premultiplied(pixel[0], pixel[1], pixel[2], 255))
+        let pixel = screenshot.get_pixel(x as u32, y as u32);
+        Some(egui::Color32::from_rgb(pixel[0], pixel[1], pixel[2]))
+    }
+    
+    /// Straight alpha under the cursor, only for captures that have alpha
+    #[inline]
+    fn alpha_at_cursor(&self) -> Option<u8> {
+        if !self.screenshot_alpha {
+            return None;
+        }
+        let screenshot = self.screenshot.as_ref()?;
+        let (x, y) = self.pixel_under_cursor();
+        if x < 0 || y < 0 || x as u32 >= screenshot.width() || y as u32 >= screenshot.height() {
+            return None;
+        }
+        Some(screenshot.get_pixel(x as u32, y as u32)[3])
     }
     
     // OPTIMIZED: Non-blocking clipboard operations
-    fn copy_to_clipboard(&self, color: egui::Color32) {
-        let hex = format!("#{:02X}{:02X}{:02X}", color.r(), color.g(), color.b());
-        
-        // Spawn background thread for all I/O operations
-        let hex_clone = hex.clone();
+    fn copy_to_clipboard(&self, color: egui::Color32, alpha: Option<u8>) {
         let color_rgb = (color.r(), color.g(), color.b());
+        let config = self.config.clone();
         
-        std::thread::spawn(move || {
-            // Save to history
-            match ColorHistory::load() {
-                Ok(mut history) => {
-                    history.add_color(hex_clone.clone(), color_rgb);
-                }
-                Err(_) => {
-                    let mut history = ColorHistory::default();
-                    history.add_color(hex_clone.clone(), color_rgb);
-                }
-            }
-            
-            // Copy to clipboard
-            if let Ok(mut clipboard) = Clipboard::new() {
-                clipboard.set_text(&hex_clone).ok();
-            }
-        });
+        // Spawn background thread for all I/O operations
+        track(std::thread::spawn(move || record_pick(color_rgb, alpha, &config)));
     }
     
     #[inline]
@@ -130,10 +622,22 @@ impl ColorPicker {
         egui::vec2(offset_x, offset_y)
     }
 
+    /// Top-left corner and cell size of the magnifier grid. Cells are whole
+    /// physical pixels, so they stay crisp and equally sized at fractional
+    /// scale factors.
+    fn magnifier_geometry(ctx: &egui::Context, mag_pos: egui::Pos2, mag_size: f32) -> (egui::Pos2, f32) {
+        let pixels_per_point = ctx.pixels_per_point();
+        let cells = (MAGNIFIER_RADIUS * 2 + 1) as f32;
+        let pixel_size = ((mag_size / cells) * pixels_per_point).round().max(1.0) / pixels_per_point;
+        let mag_pos = (mag_pos.to_vec2() * pixels_per_point).round() / pixels_per_point;
+        (mag_pos.to_pos2(), pixel_size)
+    }
+    
     fn draw_magnifier(&self, ui: &mut egui::Ui, mag_pos: egui::Pos2, mag_size: f32) {
-        let zoom = 5;
-        let pixel_size = mag_size / 11.0;
-        let mag_rect = egui::Rect::from_min_size(mag_pos, egui::vec2(mag_size, mag_size));
+        let zoom = MAGNIFIER_RADIUS;
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        let (mag_pos, pixel_size) = Self::magnifier_geometry(ui.ctx(), mag_pos, mag_size);
+        let mag_rect = egui::Rect::from_min_size(mag_pos, egui::vec2(pixel_size * 11.0, pixel_size * 11.0));
         
         // Draw blurred shadow FIRST (before content)
         draw_blurred_shadow(ui, mag_rect, 4.0, 20.0, egui::vec2(4.0, 4.0));
@@ -141,8 +645,8 @@ impl ColorPicker {
         // Draw magnifier content
         if let Some(screenshot) = &self.screenshot {
             // OPTIMIZED: Pre-calculate bounds to reduce repeated calculations
-            let center_x = self.cursor_pos.x as i32 + self.screenshot_offset.0;
-            let center_y = self.cursor_pos.y as i32 + self.screenshot_offset.1;
+            let (center_x, center_y) = self.magnifier_center();
+            let hovered = self.hovered_cell();
             let width = screenshot.width() as i32;
             let height = screenshot.height() as i32;
             
@@ -152,7 +656,15 @@ impl ColorPicker {
                     let py = (center_y + dy).clamp(0, height - 1) as u32;
                     
                     let pixel = screenshot.get_pixel(px, py);
-                    let pixel_color = egui::Color32::from_rgb(pixel[0], pixel[1], pixel[2]);
+                    let (r, g, b) = match self.simulation {
+                        Some(cvd) => cvd.simulate((pixel[0], pixel[1], pixel[2])),
+                        None => (pixel[0], pixel[1], pixel[2]),
+                    };
+                    let pixel_color = if self.screenshot_alpha {
+                        egui::Color32::from_rgba_unmultiplied(r, g, b, pixel[3])
+                    } else {
+                        egui::Color32::from_rgb(r, g, b)
+                    };
                     
                     let cell_pos = mag_pos + egui::vec2(
                         (dx + zoom) as f32 * pixel_size,
@@ -164,60 +676,143 @@ impl ColorPicker {
                         egui::vec2(pixel_size, pixel_size),
                     );
                     
-                    ui.painter().rect_filled(cell_rect, 0.0, pixel_color);
-                    
-                    // Highlight center pixel
-                    if dx == 0 && dy == 0 {
-                        ui.painter().rect_stroke(
-                            cell_rect,
-                            0.0,
-                            egui::Stroke::new(2.0, egui::Color32::RED),
-                        );
+                    // Checkerboard behind translucent pixels, like image editors do
+                    if pixel_color.a() < 255 {
+                        let half = pixel_size / 2.0;
+                        ui.painter().rect_filled(cell_rect, 0.0, egui::Color32::from_gray(204));
+                        for (cx, cy) in [(0.0, 0.0), (half, half)] {
+                            ui.painter().rect_filled(
+                                egui::Rect::from_min_size(cell_pos + egui::vec2(cx, cy), egui::vec2(half, half)),
+                                0.0,
+                                egui::Color32::from_gray(153),
+                            );
+                        }
                     }
+                    ui.painter().rect_filled(cell_rect, 0.0, pixel_color);
                 }
             }
+            
+            if self.config.show_grid && pixel_size * pixels_per_point >= 4.0 {
+                let stroke = egui::Stroke::new(1.0 / pixels_per_point, egui::Color32::from_rgba_unmultiplied(128, 128, 128, 90));
+                for i in 1..(zoom * 2 + 1) {
+                    let offset = i as f32 * pixel_size;
+                    ui.painter().line_segment([mag_pos + egui::vec2(offset, 0.0), mag_pos + egui::vec2(offset, mag_rect.height())], stroke);
+                    ui.painter().line_segment([mag_pos + egui::vec2(0.0, offset), mag_pos + egui::vec2(mag_rect.width(), offset)], stroke);
+                }
+            }
+            
+            // Outline the center pixel, and the hovered one of a pinned
+            // magnifier, in black or white depending on what's under it
+            let cell_at = |(dx, dy): (i32, i32)| {
+                let px = (center_x + dx).clamp(0, width - 1) as u32;
+                let py = (center_y + dy).clamp(0, height - 1) as u32;
+                let pixel = screenshot.get_pixel(px, py);
+                let rgb = match self.simulation {
+                    Some(cvd) => cvd.simulate((pixel[0], pixel[1], pixel[2])),
+                    None => (pixel[0], pixel[1], pixel[2]),
+                };
+                let rect = egui::Rect::from_min_size(
+                    mag_pos + egui::vec2((dx + zoom) as f32 * pixel_size, (dy + zoom) as f32 * pixel_size),
+                    egui::vec2(pixel_size, pixel_size),
+                );
+                (rect, contrast_color(rgb))
+            };
+            
+            let (rect, color) = cell_at((0, 0));
+            ui.painter().rect_stroke(rect, 0.0, egui::Stroke::new(2.0, color));
+            if let Some(cell) = hovered.filter(|&cell| cell != (0, 0)) {
+                let (rect, color) = cell_at(cell);
+                ui.painter().add(egui::Shape::dashed_line(
+                    &[rect.left_top(), rect.right_top(), rect.right_bottom(), rect.left_bottom(), rect.left_top()],
+                    egui::Stroke::new(1.5, color),
+                    3.0,
+                    2.0,
+                ));
+            }
         }
         
         // Draw white border on top
         ui.painter().rect_stroke(
             mag_rect,
             4.0,
-            egui::Stroke::new(3.0, egui::Color32::WHITE),
+            egui::Stroke::new(3.0, self.palette.overlay_text),
         );
     }
 
-    fn draw_color_info(&self, ui: &mut egui::Ui, color: egui::Color32, mag_pos: egui::Pos2, mag_size: f32, info_height: f32) {
+    /// Draws the info box next to the magnifier and returns where it went
+    fn draw_color_info(&self, ui: &mut egui::Ui, color: egui::Color32, mag_pos: egui::Pos2, mag_size: f32, info_height: f32) -> Option<egui::Rect> {
         let padding = 16.0;
         
         // OPTIMIZED: Pre-allocate with exact capacity
         let format_count = self.config.show_hex as usize + self.config.show_rgb as usize + self.config.show_hsl as usize;
         let mut formats = Vec::with_capacity(format_count);
         
+        let alpha = self.alpha_at_cursor();
+        let managed = self.managed_at_cursor();
+        
         if self.config.show_hex {
+            let hex = hex_string((color.r(), color.g(), color.b()), alpha);
+            formats.push((
+                match (&managed, &self.color_management) {
+                    (Some(_), Some(color_management)) => format!("{} {}", hex, color_management.target().label()),
+                    _ => hex,
+                },
+                self.font(16.0),
+                self.palette.overlay_text,
+            ));
+        }
+        if let Some((raw, _)) = managed {
+            formats.push((
+                format!("raw #{:02X}{:02X}{:02X}", raw.r(), raw.g(), raw.b()),
+                self.font(12.0),
+                self.palette.overlay_muted,
+            ));
+        }
+        if self.config.show_coordinates {
+            // Desktop coordinates for the screen, pixel coordinates for images
+            let (x, y) = match self.view {
+                Some(_) => self.pixel_under_cursor(),
+                None => self.mapping.to_desktop(self.pixel_under_cursor()),
+            };
+            formats.push((
+                format!("X {}  Y {}", x, y),
+                self.font(12.0),
+                self.palette.overlay_muted,
+            ));
+        }
+        if let Some(cvd) = self.simulation {
             formats.push((
-                format!("#{:02X}{:02X}{:02X}", color.r(), color.g(), color.b()),
-                egui::FontId::monospace(16.0),
-                egui::Color32::WHITE,
+                format!("{} {}", cvd.label(), hex_string(cvd.simulate((color.r(), color.g(), color.b())), None)),
+                self.font(12.0),
+                self.palette.accent,
             ));
         }
         if self.config.show_rgb {
+            let text = match alpha {
+                Some(a) => format!("rgba({}, {}, {}, {})", color.r(), color.g(), color.b(), alpha_fraction(a)),
+                None => format!("RGB({}, {}, {})", color.r(), color.g(), color.b()),
+            };
             formats.push((
-                format!("RGB({}, {}, {})", color.r(), color.g(), color.b()),
-                egui::FontId::monospace(13.0),
-                egui::Color32::from_gray(200),
+                text,
+                self.font(13.0),
+                self.palette.overlay_text,
             ));
         }
         if self.config.show_hsl {
             let (h, s, l) = rgb_to_hsl(color.r(), color.g(), color.b());
+            let text = match alpha {
+                Some(a) => format!("hsla({:.0}, {:.0}%, {:.0}%, {})", h, s * 100.0, l * 100.0, alpha_fraction(a)),
+                None => format!("HSL({:.0}, {:.0}%, {:.0}%)", h, s * 100.0, l * 100.0),
+            };
             formats.push((
-                format!("HSL({:.0}, {:.0}%, {:.0}%)", h, s * 100.0, l * 100.0),
-                egui::FontId::monospace(13.0),
-                egui::Color32::from_gray(200),
+                text,
+                self.font(13.0),
+                self.palette.overlay_text,
             ));
         }
         
         if formats.is_empty() {
-            return;
+            return None;
         }
         
         // Find max width
@@ -246,7 +841,7 @@ impl ColorPicker {
         ui.painter().rect_filled(
             text_bg,
             4.0,
-            egui::Color32::from_black_alpha(200),
+            self.palette.overlay,
         );
         
         // Draw text
@@ -261,12 +856,14 @@ impl ColorPicker {
                 font,
                 color,
             );
-            current_y += 20.0;
+            current_y += 20.0 * self.config.theme.font_scale;
         }
+        
+        Some(text_bg)
     }
 
     fn draw_crosshair(&self, ui: &mut egui::Ui) {
-        let crosshair_size = 20.0;
+        let crosshair_size = self.config.theme.crosshair_size;
         let shadow_layers = 12;
         
         // OPTIMIZED: Pre-calculate line endpoints
@@ -300,7 +897,7 @@ impl ColorPicker {
         }
         
         // Crosshair
-        let crosshair_color = egui::Color32::WHITE;
+        let crosshair_color = self.palette.crosshair;
         ui.painter().line_segment([h_start, h_end], egui::Stroke::new(2.0, crosshair_color));
         ui.painter().line_segment([v_start, v_end], egui::Stroke::new(2.0, crosshair_color));
     }
@@ -320,6 +917,14 @@ impl ColorPicker {
         const MAX_DISTANCE: f32 = 150.0;
         const SMOOTHING: f32 = 0.15;
         
+        if self.pinned.is_some() {
+            return;
+        }
+        if self.config.theme.reduced_motion {
+            self.magnifier_pos = self.cursor_pos;
+            return;
+        }
+        
         let target_pos = self.cursor_pos;
         let current_distance = self.magnifier_pos.distance(target_pos);
         
@@ -334,34 +939,388 @@ impl ColorPicker {
         self.magnifier_pos.y += (target_pos.y - self.magnifier_pos.y) * SMOOTHING;
     }
 
+    /// Scroll zooms around the cursor, right or middle drag and the arrow
+    /// keys pan, `0` fits the image to the window and `1` shows it at 100%
+    fn handle_view_input(&mut self, ctx: &egui::Context, area: egui::Rect) {
+        let cursor = self.cursor_pos;
+        let Some(view) = &mut self.view else {
+            return;
+        };
+        let Some(image) = &self.screenshot else {
+            return;
+        };
+        let image_size = egui::vec2(image.width() as f32, image.height() as f32);
+        
+        // 100% means one image pixel per physical pixel
+        let actual_size = 1.0 / ctx.pixels_per_point();
+        
+        if !view.fitted {
+            view.fit(image_size, area, actual_size);
+            view.fitted = true;
+        }
+        
+        ctx.input(|i| {
+            let factor = i.zoom_delta() * (i.smooth_scroll_delta.y / 200.0).exp();
+            if factor != 1.0 {
+                view.zoom_at(cursor, factor);
+            }
+            
+            if i.pointer.button_down(egui::PointerButton::Secondary) || i.pointer.button_down(egui::PointerButton::Middle) {
+                view.pan += i.pointer.delta();
+            }
+            
+            const STEP: f32 = 50.0;
+            if i.key_pressed(egui::Key::ArrowLeft) {
+                view.pan.x += STEP;
+            }
+            if i.key_pressed(egui::Key::ArrowRight) {
+                view.pan.x -= STEP;
+            }
+            if i.key_pressed(egui::Key::ArrowUp) {
+                view.pan.y += STEP;
+            }
+            if i.key_pressed(egui::Key::ArrowDown) {
+                view.pan.y -= STEP;
+            }
+            
+            if i.key_pressed(egui::Key::Num0) {
+                view.fit(image_size, area, ImageView::MAX_ZOOM);
+            }
+            if i.key_pressed(egui::Key::Num1) {
+                view.zoom_at(area.center(), actual_size / view.zoom);
+            }
+        });
+    }
+    
+    fn draw_image(&self, ui: &mut egui::Ui) {
+        let (Some(view), Some(image)) = (&self.view, &self.screenshot) else {
+            return;
+        };
+        
+        let rect = egui::Rect::from_min_size(
+            view.pan.to_pos2(),
+            egui::vec2(image.width() as f32, image.height() as f32) * view.zoom,
+        );
+        ui.painter().image(
+            view.texture.id(),
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+        
+        let (x, y) = self.pixel_under_cursor();
+        ui.painter().text(
+            ui.max_rect().left_bottom() + egui::vec2(12.0, -12.0),
+            egui::Align2::LEFT_BOTTOM,
+            format!(
+                "{} × {}  ·  ({}, {})  ·  {:.0}%  ·  scroll to zoom, right-drag to pan, 0 fit, 1 actual size, Esc cancel",
+                image.width(), image.height(), x, y, view.zoom * ui.ctx().pixels_per_point() * 100.0
+            ),
+            egui::FontId::proportional(12.0),
+            self.palette.overlay_muted,
+        );
+    }
+
     #[inline]
     fn handle_input(&mut self, ctx: &egui::Context) -> bool {
+        let (pressed, released, shift) = ctx.input(|i| (i.pointer.primary_pressed(), i.pointer.primary_released(), i.modifiers.shift));
+        
+        if ctx.input(|i| i.key_pressed(egui::Key::M)) {
+            self.measuring = !self.measuring;
+            self.measurement = None;
+        }
+        
+        // V cycles the simulated deficiency, Shift+V extends it to the whole screenshot
+        if ctx.input(|i| i.key_pressed(egui::Key::V)) {
+            if shift {
+                self.simulate_screen = !self.simulate_screen;
+                self.simulation = self.simulation.or(Some(Cvd::ALL[0]));
+            } else {
+                self.simulation = Cvd::cycle(self.simulation);
+            }
+        }
+        
+        // Space pins the magnifier, so the pointer can hover its cells
+        if ctx.input(|i| i.key_pressed(egui::Key::Space)) {
+            self.pinned = match self.pinned {
+                Some(_) => None,
+                None => Some(self.cursor_pos),
+            };
+        }
+        
+        if pressed {
+            self.drag_start = Some(self.cursor_pos);
+        }
+        if let Some(start) = self.drag_start {
+            if start.distance(self.cursor_pos) > DRAG_THRESHOLD && self.pinned.is_none() {
+                self.selecting = true;
+            }
+        }
+        
+        if self.measuring {
+            if released && self.selecting {
+                self.measurement = self.current_measurement(ctx);
+            }
+            if released {
+                self.drag_start = None;
+                self.selecting = false;
+            }
+            
+            // C or Enter copies the last measurement and closes, like a pick
+            if ctx.input(|i| i.key_pressed(egui::Key::C) || i.key_pressed(egui::Key::Enter)) {
+                if let Some(measurement) = self.measurement {
+                    self.copy_measurement(measurement);
+                    return true;
+                }
+            }
+        } else if released && self.selecting {
+            // Shift+release saves to a file, a plain release copies the crop
+            self.finish_selection(shift);
+            return true;
+        }
+        
         // OPTIMIZED: Check click first (more common action)
-        if ctx.input(|i| i.pointer.primary_clicked()) {
+        // Only releases of a press we saw count, not one left over from a cancelled drag
+        if released && self.drag_start.take().is_some() && !self.measuring {
+            self.receive_color_management(true);
             if let Some(color) = self.get_color_at_cursor() {
-                self.copy_to_clipboard(color);
+                let alpha = self.alpha_at_cursor();
+                if self.view.is_some() {
+                    // Image picks are often scripted, leave the result on stdout too
+                    println!("{}", hex_string((color.r(), color.g(), color.b()), alpha));
+                }
+                self.copy_to_clipboard(color, alpha);
                 return true;
             }
         }
         
-        // Check for escape key
-        ctx.input(|i| i.key_pressed(egui::Key::Escape))
+        // Escape drops a selection in progress or unpins first, then closes the picker
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            if self.selecting {
+                self.drag_start = None;
+                self.selecting = false;
+                return false;
+            }
+            if self.pinned.take().is_some() {
+                return false;
+            }
+            return true;
+        }
+        
+        false
     }
-}
-
-impl eframe::App for ColorPicker {
-    #[inline]
-    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
-        egui::Rgba::TRANSPARENT.to_array()
+    
+    fn draw_measurement(&self, ui: &mut egui::Ui) {
+        let painter = ui.painter();
+        let font = self.font(12.0);
+        
+        if self.measuring {
+            let hint = "RULER  drag to measure · Alt: no snapping · C: copy · M: exit";
+            draw_badge(painter, &self.palette, font.clone(), egui::Align2::LEFT_TOP, ui.max_rect().left_top() + egui::vec2(16.0, 16.0), hint);
+        }
+        
+        let Some((start, end)) = self.current_measurement(ui.ctx()) else {
+            return;
+        };
+        let (a, b) = (self.pos_of_pixel(start), self.pos_of_pixel(end));
+        let corner = egui::pos2(b.x, a.y);
+        
+        let accent = self.palette.accent;
+        let shadow = egui::Stroke::new(3.0, egui::Color32::from_black_alpha(160));
+        for (from, to) in [(a, corner), (corner, b)] {
+            painter.line_segment([from, to], shadow);
+            painter.add(egui::Shape::dashed_line(&[from, to], egui::Stroke::new(1.0, accent), 4.0, 3.0));
+        }
+        painter.line_segment([a, b], shadow);
+        painter.line_segment([a, b], egui::Stroke::new(1.5, egui::Color32::WHITE));
+        for point in [a, b] {
+            painter.circle(point, 3.0, accent, egui::Stroke::new(1.0, egui::Color32::BLACK));
+        }
+        
+        let galley = painter.layout_no_wrap(measurement_text(start, end), font, self.palette.overlay_text);
+        let pos = a.lerp(b, 0.5) + egui::vec2(10.0, 10.0);
+        painter.rect_filled(egui::Rect::from_min_size(pos, galley.size() + egui::vec2(12.0, 8.0)), 4.0, self.palette.overlay);
+        painter.galley(pos + egui::vec2(6.0, 4.0), galley, self.palette.overlay_text);
     }
     
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        if self.should_close {
-            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    /// Whether the window shows the screenshot (or image) rather than the live desktop
+    fn frozen(&self) -> bool {
+        self.view.is_some() || self.config.overlay_mode == OverlayMode::Freeze || self.live_failed
+    }
+    
+    /// Freeze mode: paints the screenshot over the desktop, so moving video
+    /// or animations don't differ from what gets sampled
+    fn draw_background(&mut self, ui: &mut egui::Ui) {
+        if self.view.is_some() || !self.frozen() {
+            return;
+        }
+        let Some(screenshot) = &self.screenshot else {
+            return;
+        };
+        
+        let texture = self.background.get_or_insert_with(|| load_texture(ui.ctx(), "frozen-screenshot", screenshot));
+        let rect = egui::Rect::from_min_max(
+            self.mapping.to_pos((0, 0)),
+            self.mapping.to_pos((screenshot.width() as i32, screenshot.height() as i32)),
+        );
+        ui.painter().image(
+            texture.id(),
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+    }
+    
+    /// Live mode: folds finished captures into the screenshot and asks for
+    /// the next one around the cursor
+    fn update_live(&mut self) {
+        if self.frozen() {
+            return;
+        }
+        let Some(screenshot) = &mut self.screenshot else {
+            return;
+        };
+        let live = self.live.get_or_insert_with(LiveCapture::start);
+        
+        match live.results.try_recv() {
+            Ok(Ok(region)) => {
+                live.pending = false;
+                // A region at another scale wouldn't line up pixel for pixel
+                if (region.scale - self.screenshot_scale).abs() < 0.01 {
+                    let x = ((region.offset.0 - self.screenshot_offset.0) as f32 * self.screenshot_scale).round() as i64;
+                    let y = ((region.offset.1 - self.screenshot_offset.1) as f32 * self.screenshot_scale).round() as i64;
+                    image::imageops::replace(screenshot, &region.image, x, y);
+                }
+            }
+            Ok(Err(e)) => {
+                eprintln!("⚠️  Live capture unavailable, freezing the overlay: {}", e);
+                self.live = None;
+                self.live_failed = true;
+                return;
+            }
+            Err(_) => {}
+        }
+        
+        // Our own drawing would end up in the capture, wait while anything
+        // but the system cursor is near the sampled area
+        let center = self.pinned.unwrap_or(self.cursor_pos);
+        if live.pending
+            || live.last_request.elapsed() < LIVE_INTERVAL
+            || self.selecting
+            || self.measuring
+            || self.simulate_screen
+            || covers_live_area(&self.magnifier_rects, center, self.mapping.units_per_point)
+        {
             return;
         }
         
-        ctx.set_cursor_icon(egui::CursorIcon::None);
+        let (x, y) = self.mapping.to_desktop(self.mapping.to_pixel(center));
+        let side = (LIVE_RADIUS * 2 + 1) as u32;
+        if live.requests.send((x - LIVE_RADIUS, y - LIVE_RADIUS, side, side)).is_ok() {
+            live.pending = true;
+            live.last_request = Instant::now();
+        }
+    }
+    
+    /// Covers the screenshot with its simulated version, built on first use
+    /// and again whenever the deficiency changes
+    fn draw_simulated_screen(&mut self, ui: &mut egui::Ui) {
+        let (Some(cvd), true, Some(screenshot)) = (self.simulation, self.simulate_screen, &self.screenshot) else {
+            return;
+        };
+        
+        if self.simulated_texture.as_ref().map(|(simulated, _)| *simulated) != Some(cvd) {
+            let texture = load_texture(ui.ctx(), "simulated-screenshot", &cvd.simulate_image(screenshot));
+            self.simulated_texture = Some((cvd, texture));
+        }
+        let Some((_, texture)) = &self.simulated_texture else {
+            return;
+        };
+        
+        let rect = egui::Rect::from_min_max(
+            self.pos_of_pixel((0, 0)),
+            self.pos_of_pixel((screenshot.width() as i32, screenshot.height() as i32)),
+        );
+        ui.painter().image(
+            texture.id(),
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+    }
+    
+    fn draw_simulation_hint(&self, ui: &mut egui::Ui) {
+        let Some(cvd) = self.simulation else {
+            return;
+        };
+        
+        let hint = format!(
+            "{}  V: next · Shift+V: {}",
+            cvd.label().to_uppercase(),
+            if self.simulate_screen { "magnifier only" } else { "whole screen" }
+        );
+        draw_badge(ui.painter(), &self.palette, self.font(12.0), egui::Align2::RIGHT_TOP, ui.max_rect().right_top() + egui::vec2(-16.0, 16.0), &hint);
+    }
+    
+    fn draw_pinned_hint(&self, ui: &mut egui::Ui) {
+        if self.pinned.is_some() {
+            let hint = "PINNED  hover the magnifier to inspect cells · click to pick · Space: unpin";
+            draw_badge(ui.painter(), &self.palette, self.font(12.0), egui::Align2::CENTER_TOP, ui.max_rect().center_top() + egui::vec2(0.0, 16.0), hint);
+        }
+    }
+    
+    fn draw_selection(&self, ui: &mut egui::Ui) {
+        let (Some(start), true, false) = (self.drag_start, self.selecting, self.measuring) else {
+            return;
+        };
+        let Some((_, _, width, height)) = self.selection_pixels() else {
+            return;
+        };
+        
+        let rect = egui::Rect::from_two_pos(start, self.cursor_pos);
+        let painter = ui.painter();
+        
+        // Dim everything outside the selection
+        let screen = ui.max_rect();
+        let dim = egui::Color32::from_black_alpha(90);
+        painter.rect_filled(egui::Rect::from_min_max(screen.min, egui::pos2(screen.right(), rect.top())), 0.0, dim);
+        painter.rect_filled(egui::Rect::from_min_max(egui::pos2(screen.left(), rect.bottom()), screen.max), 0.0, dim);
+        painter.rect_filled(egui::Rect::from_min_max(egui::pos2(screen.left(), rect.top()), egui::pos2(rect.left(), rect.bottom())), 0.0, dim);
+        painter.rect_filled(egui::Rect::from_min_max(egui::pos2(rect.right(), rect.top()), egui::pos2(screen.right(), rect.bottom())), 0.0, dim);
+        
+        painter.rect_stroke(rect.expand(1.0), 0.0, egui::Stroke::new(1.0, egui::Color32::from_black_alpha(160)));
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, self.palette.crosshair));
+        
+        // Dimensions just below the selection, or above it near the screen edge
+        let label = format!("{} × {}", width, height);
+        let font = self.font(12.0);
+        let galley = painter.layout_no_wrap(label, font, self.palette.overlay_text);
+        let mut pos = egui::pos2(rect.left(), rect.bottom() + 6.0);
+        if pos.y + galley.size().y + 8.0 > screen.bottom() {
+            pos.y = rect.top() - galley.size().y - 14.0;
+        }
+        let background = egui::Rect::from_min_size(pos, galley.size() + egui::vec2(12.0, 8.0));
+        painter.rect_filled(background, 4.0, self.palette.overlay);
+        painter.galley(pos + egui::vec2(6.0, 4.0), galley, self.palette.overlay_text);
+    }
+
+    /// Draws one frame of the overlay, returning `true` once the pick is done
+    /// (color copied or cancelled) and the window should go away
+    pub fn run_frame(&mut self, ctx: &egui::Context) -> bool {
+        if self.cancel_flag.as_ref().is_some_and(|flag| flag.swap(false, Ordering::SeqCst)) {
+            self.should_close = true;
+        }
+        
+        if self.should_close {
+            return true;
+        }
+        
+        // Live mode can't draw its own crosshair over the pixels it captures
+        ctx.set_cursor_icon(if self.frozen() { egui::CursorIcon::None } else { egui::CursorIcon::Crosshair });
+        
+        self.mapping.update(ctx);
+        self.receive_color_management(false);
+        self.palette = Palette::new(&self.config.theme, self.config.theme.is_dark(ctx));
         
         // Update positions
         self.update_cursor_position(ctx);
@@ -370,48 +1329,85 @@ impl eframe::App for ColorPicker {
         // Handle input
         if self.handle_input(ctx) {
             self.should_close = true;
-            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-            return;
+            return true;
         }
         
+        self.handle_view_input(ctx, ctx.screen_rect());
+        self.update_live();
+        
         egui::CentralPanel::default()
             .frame(egui::Frame::none().fill(egui::Color32::TRANSPARENT))
             .show(ctx, |ui| {
                 let screen_rect = ui.max_rect();
                 
+                self.draw_background(ui);
+                self.draw_image(ui);
+                self.draw_simulated_screen(ui);
+                
+                self.magnifier_rects = [egui::Rect::NOTHING; 2];
                 if let Some(color) = self.get_color_at_cursor() {
                     let mag_size = self.config.preview_size as f32;
                     
                     // OPTIMIZED: Calculate line count using boolean arithmetic
                     let line_count = self.config.show_hex as usize 
                         + self.config.show_rgb as usize 
-                        + self.config.show_hsl as usize;
+                        + self.config.show_hsl as usize
+                        + self.managed_at_cursor().is_some() as usize
+                        + self.simulation.is_some() as usize
+                        + self.config.show_coordinates as usize;
                     let info_height = if line_count > 0 { 
-                        15.0 + (line_count as f32 * 20.0) 
+                        15.0 + (line_count as f32 * 20.0 * self.config.theme.font_scale) 
                     } else { 
                         0.0 
                     };
                     
                     // Calculate and smooth magnifier offset
                     let target_offset = self.calculate_magnifier_offset(mag_size, info_height, screen_rect);
-                    const OFFSET_SMOOTHING: f32 = 0.25;
-                    self.magnifier_offset.x += (target_offset.x - self.magnifier_offset.x) * OFFSET_SMOOTHING;
-                    self.magnifier_offset.y += (target_offset.y - self.magnifier_offset.y) * OFFSET_SMOOTHING;
+                    let smoothing = if self.config.theme.reduced_motion { 1.0 } else { 0.25 };
+                    self.magnifier_offset.x += (target_offset.x - self.magnifier_offset.x) * smoothing;
+                    self.magnifier_offset.y += (target_offset.y - self.magnifier_offset.y) * smoothing;
                     
                     let mag_pos = self.magnifier_pos + self.magnifier_offset;
+                    self.magnifier_cells = Some(Self::magnifier_geometry(ctx, mag_pos, mag_size));
                     
                     // Draw components
                     self.draw_magnifier(ui, mag_pos, mag_size);
+                    self.magnifier_rects[0] = egui::Rect::from_min_size(mag_pos, egui::vec2(mag_size, mag_size));
                     
                     if info_height > 0.0 {
-                        self.draw_color_info(ui, color, mag_pos, mag_size, info_height);
+                        if let Some(info_rect) = self.draw_color_info(ui, color, mag_pos, mag_size, info_height) {
+                            self.magnifier_rects[1] = info_rect;
+                        }
                     }
                 }
                 
-                self.draw_crosshair(ui);
+                self.draw_selection(ui);
+                self.draw_measurement(ui);
+                self.draw_simulation_hint(ui);
+                self.draw_pinned_hint(ui);
+                if self.frozen() {
+                    self.draw_crosshair(ui);
+                }
             });
         
         ctx.request_repaint();
+        false
+    }
+}
+
+impl eframe::App for ColorPicker {
+    #[inline]
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        if self.view.is_some() {
+            return egui::Rgba::from(self.palette.background).to_array();
+        }
+        egui::Rgba::TRANSPARENT.to_array()
+    }
+    
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if self.run_frame(ctx) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
     }
 }
 
@@ -435,50 +1431,331 @@ fn draw_blurred_shadow(ui: &mut egui::Ui, rect: egui::Rect, rounding: f32, blur_
     }
 }
 
-// Helper function to convert RGB to HSL
-#[inline]
-fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
-    let r = r as f32 / 255.0;
-    let g = g as f32 / 255.0;
-    let b = b as f32 / 255.0;
+/// A short hint in a dark box, anchored at `pos`
+fn draw_badge(painter: &egui::Painter, palette: &Palette, font: egui::FontId, anchor: egui::Align2, pos: egui::Pos2, text: &str) {
+    let galley = painter.layout_no_wrap(text.to_string(), font, palette.overlay_text);
+    let rect = anchor.anchor_size(pos, galley.size() + egui::vec2(12.0, 8.0));
+    painter.rect_filled(rect, 4.0, palette.overlay);
+    painter.galley(rect.min + egui::vec2(6.0, 4.0), galley, palette.overlay_text);
+}
+
+/// Black or white, whichever stands out against `rgb`
+fn contrast_color((r, g, b): (u8, u8, u8)) -> egui::Color32 {
+    let luminance = 0.2126 * crate::color::srgb_to_linear(r)
+        + 0.7152 * crate::color::srgb_to_linear(g)
+        + 0.0722 * crate::color::srgb_to_linear(b);
+    // Where contrast against black and against white is equal
+    if luminance > 0.179 {
+        egui::Color32::BLACK
+    } else {
+        egui::Color32::WHITE
+    }
+}
+
+/// Picks, crops and measurements still saving, copying or waiting on their
+/// notification's actions
+static PENDING_PICKS: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// Adds background work from the overlay to [`PENDING_PICKS`]
+fn track(handle: JoinHandle<()>) {
+    let mut pending = PENDING_PICKS.lock().unwrap_or_else(|e| e.into_inner());
+    pending.retain(|pick| !pick.is_finished());
+    pending.push(handle);
+}
+
+/// How long pick notifications stay up, and answer their actions
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(6);
+
+/// Waits for picks started from the overlay or the portal, so a one-shot picker process
+/// doesn't exit before the color is copied or its notification is answered
+pub fn wait_for_picks() {
+    let pending = std::mem::take(&mut *PENDING_PICKS.lock().unwrap_or_else(|e| e.into_inner()));
+    for pick in pending {
+        pick.join().ok();
+    }
+}
+
+/// Saves a picked color to the history, copies its hex code and, when
+/// enabled, confirms the pick with a desktop notification
+pub fn record_pick(color_rgb: (u8, u8, u8), alpha: Option<u8>, config: &Config) {
+    let hex = hex_string(color_rgb, alpha);
     
-    let max = r.max(g).max(b);
-    let min = r.min(g).min(b);
-    let delta = max - min;
+    // Save to history
+    match ColorHistory::load() {
+        Ok(mut history) => {
+            history.add_color(hex.clone(), color_rgb);
+        }
+        Err(_) => {
+            let mut history = ColorHistory::default();
+            history.add_color(hex.clone(), color_rgb);
+        }
+    }
     
-    let l = (max + min) / 2.0;
+    // Copy to clipboard
+    let copied = clipboard::copy_text(&hex, config.copy_to_primary);
+    if let Err(e) = &copied {
+        eprintln!("❌ Failed to copy {}: {}", hex, e);
+    }
     
-    let s = if delta == 0.0 {
-        0.0
-    } else {
-        delta / (1.0 - (2.0 * l - 1.0).abs())
+    if config.notify_on_pick {
+        notify_pick(ParsedColor { rgb: color_rgb, alpha }, &hex, copied, config.copy_to_primary);
+    }
+}
+
+/// Shows what was copied, or why it wasn't, and handles the notification's actions
+fn notify_pick(color: ParsedColor, hex: &str, copied: Result<(), String>, primary: bool) {
+    let rgb = Format::Rgb.format(color);
+    let (summary, body) = match copied {
+        Ok(()) => (format!("Copied {}", hex), format!("{}\n{}", rgb, Format::Hsl.format(color))),
+        Err(e) => (format!("Picked {}", hex), format!("Couldn't copy to the clipboard: {}", e)),
+    };
+    let actions = [("rgb", "Copy as RGB"), ("history", "Open history")];
+    
+    let notification = match notify::send(&summary, &body, &swatch(color), &actions, NOTIFICATION_TIMEOUT) {
+        Ok(notification) => notification,
+        Err(e) => {
+            eprintln!("⚠️  Pick notification failed: {}", e);
+            return;
+        }
     };
     
-    let h = if delta == 0.0 {
-        0.0
-    } else if max == r {
-        60.0 * (((g - b) / delta) % 6.0)
-    } else if max == g {
-        60.0 * (((b - r) / delta) + 2.0)
+    match notification.wait_for_action().as_deref() {
+        Some("rgb") => match clipboard::copy_text(&rgb, primary) {
+            Ok(()) => println!("📋 Copied {}", rgb),
+            Err(e) => eprintln!("❌ Failed to copy {}: {}", rgb, e),
+        },
+        Some("history") => {
+            let exe_path = std::env::current_exe()
+                .ok()
+                .and_then(|p| p.to_str().map(|s| s.to_string()))
+                .unwrap_or_else(|| "yoinkctl".to_string());
+            
+            if let Err(e) = std::process::Command::new(&exe_path).spawn() {
+                eprintln!("❌ Failed to open history: {}", e);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Notification icon, translucent colors are shown over a checkerboard
+fn swatch(color: ParsedColor) -> RgbaImage {
+    const SIZE: u32 = 64;
+    let (r, g, b) = color.rgb;
+    let alpha = color.alpha.unwrap_or(255) as u32;
+    
+    RgbaImage::from_fn(SIZE, SIZE, |x, y| {
+        let checker = if (x / 8 + y / 8) % 2 == 0 { 204 } else { 153 };
+        let blend = |c: u8| ((c as u32 * alpha + checker * (255 - alpha)) / 255) as u8;
+        image::Rgba([blend(r), blend(g), blend(b), 255])
+    })
+}
+
+/// Uploads an image as a pixel-exact texture. GPUs cap texture sizes, so huge
+/// images are drawn from a smaller copy while sampling keeps using the original.
+fn load_texture(ctx: &egui::Context, name: &str, image: &RgbaImage) -> egui::TextureHandle {
+    const MAX_TEXTURE_SIDE: u32 = 8192;
+    let resized;
+    let display = if image.width().max(image.height()) > MAX_TEXTURE_SIDE {
+        let scale = MAX_TEXTURE_SIDE as f32 / image.width().max(image.height()) as f32;
+        resized = image::imageops::resize(
+            image,
+            (image.width() as f32 * scale) as u32,
+            (image.height() as f32 * scale) as u32,
+            image::imageops::FilterType::Triangle,
+        );
+        &resized
     } else {
-        60.0 * (((r - g) / delta) + 4.0)
+        image
     };
     
-    let h = if h < 0.0 { h + 360.0 } else { h };
+    ctx.load_texture(
+        name,
+        egui::ColorImage::from_rgba_unmultiplied(
+            [display.width() as usize, display.height() as usize],
+            display.as_raw(),
+        ),
+        egui::TextureOptions::NEAREST,
+    )
+}
+
+/// e.g. `120 × 48 px, diagonal 129.2 px`
+fn measurement_text(start: (i32, i32), end: (i32, i32)) -> String {
+    let (width, height) = ((end.0 - start.0).abs(), (end.1 - start.1).abs());
+    let diagonal = ((width * width + height * height) as f32).sqrt();
+    format!("{} × {} px, diagonal {:.1} px", width, height, diagonal)
+}
+
+fn copy_crop(crop: RgbaImage) -> Result<String, String> {
+    let (width, height) = crop.dimensions();
+    clipboard::copy_image(crop)?;
+    
+    Ok(format!("Copied {}×{} region", width, height))
+}
+
+/// Saves under `~/Pictures/yoinkctl`, falling back to the home directory
+fn save_crop(crop: &RgbaImage) -> Result<String, String> {
+    let mut path = dirs::picture_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| std::path::PathBuf::from("."));
+    path.push("yoinkctl");
+    std::fs::create_dir_all(&path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    path.push(format!("yoinkctl-{}.png", chrono::Local::now().format("%Y%m%d-%H%M%S")));
     
-    (h, s, l)
+    crop.save(&path)
+        .map_err(|e| format!("Failed to save {}: {}", path.display(), e))?;
+    
+    Ok(format!("Saved {}×{} region to {}", crop.width(), crop.height(), path.display()))
 }
 
-pub fn capture_all_screens() -> (Option<RgbaImage>, (i32, i32)) {
-    if let Ok(monitors) = Monitor::all() {
-        if let Some(monitor) = monitors.first() {
-            if let Ok(image) = monitor.capture_image() {
-                let x_offset = monitor.x();
-                let y_offset = monitor.y();
-                return (Some(image), (x_offset, y_offset));
+/// Picks through the compositor's own color picker, for Wayland sessions
+/// where the screenshot portal isn't allowed
+pub fn pick_with_portal() {
+    match portal::pick_color() {
+        Ok(color_rgb) => {
+            let config = Config::load().unwrap_or_default();
+            track(std::thread::spawn(move || record_pick(color_rgb, None, &config)));
+            println!("🎨 Picked #{:02X}{:02X}{:02X}", color_rgb.0, color_rgb.1, color_rgb.2);
+        }
+        Err(e) => eprintln!("⚠️  Portal color pick failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A mapping updated the way a frame would, for a window whose top-left
+    /// corner is at `window_origin` points
+    fn mapping(window_origin: (f32, f32), pixels_per_point: f32, capture_offset: (i32, i32), capture_scale: f32) -> ScreenMapping {
+        let mut input = egui::RawInput::default();
+        let viewport = input.viewports.entry(input.viewport_id).or_default();
+        viewport.native_pixels_per_point = Some(pixels_per_point);
+        viewport.inner_rect = Some(egui::Rect::from_min_size(
+            egui::pos2(window_origin.0, window_origin.1),
+            egui::vec2(800.0, 600.0),
+        ));
+
+        let mut mapping = ScreenMapping::new(capture_offset, capture_scale);
+        let _ = egui::Context::default().run(input, |ctx| mapping.update(ctx));
+        mapping
+    }
+
+    #[test]
+    fn unscaled_points_are_pixels() {
+        let mapping = mapping((0.0, 0.0), 1.0, (0, 0), 1.0);
+
+        assert_eq!(mapping.to_pixel(egui::pos2(10.5, 20.9)), (10, 20));
+        assert_eq!(mapping.to_desktop((10, 20)), (10, 20));
+        assert_eq!(mapping.to_pos((10, 20)), egui::pos2(10.0, 20.0));
+    }
+
+    // Desktop units are points on macOS, where this scaling doesn't apply
+    #[test]
+    #[cfg(not(target_os = "macos"))]
+    fn scale_factor_multiplies_points() {
+        let one_and_a_half = mapping((0.0, 0.0), 1.5, (0, 0), 1.0);
+        assert_eq!(one_and_a_half.to_pixel(egui::pos2(10.0, 7.0)), (15, 10));
+        assert_eq!(one_and_a_half.to_pos((15, 9)), egui::pos2(10.0, 6.0));
+
+        let double = mapping((0.0, 0.0), 2.0, (0, 0), 1.0);
+        assert_eq!(double.to_pixel(egui::pos2(10.25, 3.0)), (20, 6));
+        assert_eq!(double.to_pos((21, 6)), egui::pos2(10.5, 3.0));
+    }
+
+    #[test]
+    fn capture_scale_divides_desktop_units() {
+        // A 2x screenshot of a desktop measured in points, like on macOS
+        let mapping = mapping((0.0, 0.0), 1.0, (0, 0), 2.0);
+
+        assert_eq!(mapping.to_pixel(egui::pos2(10.0, 10.75)), (20, 21));
+        assert_eq!(mapping.to_desktop((21, 20)), (10, 10));
+        assert_eq!(mapping.to_pos((21, 20)), egui::pos2(10.5, 10.0));
+    }
+
+    #[test]
+    #[cfg(not(target_os = "macos"))]
+    fn monitor_left_of_the_primary_has_a_negative_offset() {
+        // The desktop spans a 1920 wide monitor at x = -1920 and a 2x one at 0
+        let left = mapping((-1920.0, 0.0), 1.0, (-1920, 0), 1.0);
+        assert_eq!(left.to_pixel(egui::pos2(0.0, 0.0)), (0, 0));
+        assert_eq!(left.to_pixel(egui::pos2(1919.0, 5.0)), (1919, 5));
+        assert_eq!(left.to_desktop((0, 5)), (-1920, 5));
+
+        let right = mapping((0.0, 0.0), 2.0, (-1920, 0), 1.0);
+        assert_eq!(right.to_pixel(egui::pos2(5.0, 5.0)), (1930, 10));
+        assert_eq!(right.to_desktop((1930, 10)), (10, 10));
+        assert_eq!(right.to_pos((1930, 10)), egui::pos2(5.0, 5.0));
+    }
+
+    #[test]
+    fn window_offset_within_the_capture() {
+        let mapping = mapping((100.0, 50.0), 1.0, (-1920, -40), 1.0);
+
+        assert_eq!(mapping.to_pixel(egui::pos2(0.0, 0.0)), (2020, 90));
+        assert_eq!(mapping.to_desktop((2020, 90)), (100, 50));
+        assert_eq!(mapping.to_pos((2020, 90)), egui::pos2(0.0, 0.0));
+    }
+
+    #[test]
+    fn before_the_first_frame_the_window_covers_the_capture() {
+        let mapping = ScreenMapping::new((-1920, -40), 1.0);
+
+        assert_eq!(mapping.to_pixel(egui::pos2(0.0, 0.0)), (0, 0));
+        assert_eq!(mapping.to_desktop((0, 0)), (-1920, -40));
+    }
+
+    #[test]
+    fn pixel_corners_round_trip_under_fractional_scaling() {
+        for pixels_per_point in [1.0, 1.25, 1.5, 1.75, 2.0] {
+            for capture_scale in [1.0, 1.5, 2.0] {
+                let mapping = mapping((-853.0, 17.0), pixels_per_point, (-1920, -40), capture_scale);
+
+                for x in -2000..6000 {
+                    let pixel = (x, x / 3);
+                    assert_eq!(
+                        mapping.to_pixel(mapping.to_pos(pixel)),
+                        pixel,
+                        "at {}x scale with {}x capture",
+                        pixels_per_point,
+                        capture_scale,
+                    );
+                }
             }
         }
     }
-    
-    (None, (0, 0))
-}
\ No newline at end of file
+
+    /// The magnifier and an info box for four lines, placed the way
+    /// `calculate_magnifier_offset` does with room on every side
+    fn default_layout(cursor: egui::Pos2) -> [egui::Rect; 2] {
+        let magnifier = egui::Rect::from_min_size(cursor + egui::vec2(30.0, 30.0), egui::vec2(120.0, 120.0));
+        let info = egui::Rect::from_min_size(magnifier.left_bottom() + egui::vec2(-10.0, 10.0), egui::vec2(140.0, 95.0));
+        [magnifier, info]
+    }
+
+    #[test]
+    fn live_capture_runs_beside_the_default_magnifier() {
+        let cursor = egui::pos2(500.0, 400.0);
+
+        for units_per_point in [1.0, 1.5, 2.0] {
+            assert!(!covers_live_area(&default_layout(cursor), cursor, units_per_point));
+        }
+    }
+
+    #[test]
+    fn live_capture_waits_while_the_magnifier_is_over_it() {
+        let cursor = egui::pos2(500.0, 400.0);
+
+        // Pushed onto the cursor near a screen edge, or hovered while pinned
+        let [magnifier, info] = default_layout(cursor);
+        let near = [magnifier.translate(egui::vec2(-30.0, -30.0)), egui::Rect::NOTHING];
+        assert!(covers_live_area(&near, cursor, 1.0));
+        assert!(covers_live_area(&[egui::Rect::NOTHING, info], info.center(), 1.0));
+    }
+
+    #[test]
+    fn live_capture_ignores_nothing_drawn() {
+        assert!(!covers_live_area(&[egui::Rect::NOTHING; 2], egui::pos2(0.0, 0.0), 1.0));
+    }
+}
@@ -0,0 +1,511 @@
+use image::RgbaImage;
+
+/// Whether capture and the global hotkey should go through xdg-desktop-portal.
+///
+/// Follows the session type, `YOINKCTL_BACKEND=portal|x11` overrides it.
+pub fn should_use() -> bool {
+    match std::env::var("YOINKCTL_BACKEND").as_deref() {
+        Ok("portal") => true,
+        Ok("x11") => false,
+        _ => crate::diagnostics::session_type() == "wayland",
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use image::RgbaImage;
+    use zbus::blocking::{Connection, MessageIterator};
+    use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+    use zbus::MatchRule;
+
+    use crate::hotkey::Hotkey;
+
+    const DESTINATION: &str = "org.freedesktop.portal.Desktop";
+    const PATH: &str = "/org/freedesktop/portal/desktop";
+    const SHORTCUT_ID: &str = "pick";
+
+    // How long each request may take to answer. The longer ones can wait on
+    // the user: a permission dialog, the compositor's color picker, or the
+    // dialog confirming a shortcut
+    const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(30);
+    const PICK_COLOR_TIMEOUT: Duration = Duration::from_secs(120);
+    const CREATE_SESSION_TIMEOUT: Duration = Duration::from_secs(10);
+    const BIND_SHORTCUTS_TIMEOUT: Duration = Duration::from_secs(60);
+
+    type Results = HashMap<String, OwnedValue>;
+
+    fn connect() -> Result<Connection, String> {
+        Connection::session().map_err(|e| format!("Cannot connect to the session bus: {}", e))
+    }
+
+    fn new_token() -> String {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        format!("yoinkctl_{}_{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Calls a portal method that answers through a `Request::Response` signal.
+    ///
+    /// The request path is derived from our bus name and `handle_token`, so we
+    /// can subscribe before calling and never miss a fast response. A portal
+    /// that hasn't answered within `timeout` is told to close the request.
+    fn request<B>(conn: &Connection, interface: &str, method: &str, body: B, token: &str, timeout: Duration) -> Result<Results, String>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        // Peer-to-peer connections, as in tests, have no bus name
+        let sender = conn.unique_name()
+            .map(|name| name.trim_start_matches(':').replace('.', "_"))
+            .unwrap_or_else(|| "peer".to_string());
+        let handle = format!("{}/request/{}/{}", PATH, sender, token);
+
+        let rule = MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .interface("org.freedesktop.portal.Request")
+            .and_then(|rule| rule.member("Response"))
+            .and_then(|rule| rule.path(handle.as_str()))
+            .map_err(|e| e.to_string())?
+            .build();
+        let mut responses = MessageIterator::for_match_rule(rule, conn, Some(1))
+            .map_err(|e| format!("Failed to subscribe to portal response: {}", e))?;
+
+        conn.call_method(Some(DESTINATION), PATH, Some(interface), method, &body)
+            .map_err(|e| format!("{}.{} failed: {}", interface, method, e))?;
+
+        // The iterator has no timeout of its own, wait for it on a thread
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            sender.send(responses.next()).ok();
+        });
+
+        let message = match receiver.recv_timeout(timeout) {
+            Ok(message) => message
+                .ok_or_else(|| "Portal closed the connection".to_string())?
+                .map_err(|e| e.to_string())?,
+            Err(_) => {
+                conn.call_method(Some(DESTINATION), handle.as_str(), Some("org.freedesktop.portal.Request"), "Close", &())
+                    .ok();
+                return Err(format!("{}.{} did not answer within {:?}", interface, method, timeout));
+            }
+        };
+
+        let (response, results): (u32, Results) = message.body()
+            .deserialize()
+            .map_err(|e| format!("Invalid portal response: {}", e))?;
+
+        match response {
+            0 => Ok(results),
+            1 => Err("Cancelled".to_string()),
+            _ => Err(format!("{}.{} was denied", interface, method)),
+        }
+    }
+
+    fn take<T>(results: &mut Results, key: &str) -> Result<T, String>
+    where
+        T: TryFrom<Value<'static>>,
+    {
+        results.remove(key)
+            .and_then(|value| T::try_from(Value::from(value)).ok())
+            .ok_or_else(|| format!("Portal response is missing '{}'", key))
+    }
+
+    pub fn interface_version(interface: &str) -> Result<u32, String> {
+        let conn = connect()?;
+
+        let reply = conn
+            .call_method(Some(DESTINATION), PATH, Some("org.freedesktop.DBus.Properties"), "Get", &(interface, "version"))
+            .map_err(|e| format!("{} is not available: {}", interface, e))?;
+
+        let value: OwnedValue = reply.body().deserialize().map_err(|e| e.to_string())?;
+        u32::try_from(value).map_err(|e| e.to_string())
+    }
+
+    pub fn screenshot() -> Result<RgbaImage, String> {
+        screenshot_on(&connect()?)
+    }
+
+    fn screenshot_on(conn: &Connection) -> Result<RgbaImage, String> {
+        let token = new_token();
+
+        let options = HashMap::from([
+            ("handle_token", Value::from(token.as_str())),
+            ("interactive", Value::from(false)),
+        ]);
+
+        let mut results = request(conn, "org.freedesktop.portal.Screenshot", "Screenshot", ("", options), &token, SCREENSHOT_TIMEOUT)?;
+        let uri: String = take(&mut results, "uri")?;
+
+        let path = url::Url::parse(&uri)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .ok_or_else(|| format!("Portal returned an unusable screenshot URI: {}", uri))?;
+
+        let image = image::open(&path)
+            .map_err(|e| format!("Failed to load portal screenshot {}: {}", path.display(), e))?;
+
+        // Files the portal left in a temporary directory are ours to clean
+        // up, ones saved into e.g. ~/Pictures belong to the user
+        if is_temporary(&path) {
+            std::fs::remove_file(&path).ok();
+        }
+
+        Ok(image.to_rgba8())
+    }
+
+    fn is_temporary(path: &Path) -> bool {
+        path.starts_with(std::env::temp_dir())
+            || dirs::runtime_dir().is_some_and(|dir| path.starts_with(dir))
+    }
+
+    pub fn pick_color() -> Result<(u8, u8, u8), String> {
+        pick_color_on(&connect()?)
+    }
+
+    fn pick_color_on(conn: &Connection) -> Result<(u8, u8, u8), String> {
+        let token = new_token();
+
+        let options = HashMap::from([("handle_token", Value::from(token.as_str()))]);
+
+        let mut results = request(conn, "org.freedesktop.portal.Screenshot", "PickColor", ("", options), &token, PICK_COLOR_TIMEOUT)?;
+        let (r, g, b): (f64, f64, f64) = take(&mut results, "color")?;
+
+        let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Ok((channel(r), channel(g), channel(b)))
+    }
+
+    /// Connection to the GlobalShortcuts portal, used in place of an X11 key grab
+    pub struct GlobalShortcuts {
+        conn: Connection,
+    }
+
+    impl GlobalShortcuts {
+        pub fn connect() -> Result<Self, String> {
+            Ok(Self { conn: connect()? })
+        }
+
+        /// Calls `on_activated` with the session handle whenever one of our
+        /// shortcuts fires, for as long as the process lives
+        pub fn listen<F>(&self, on_activated: F) -> Result<(), String>
+        where
+            F: Fn(String) + Send + 'static,
+        {
+            let rule = MatchRule::builder()
+                .msg_type(zbus::message::Type::Signal)
+                .interface("org.freedesktop.portal.GlobalShortcuts")
+                .and_then(|rule| rule.member("Activated"))
+                .map_err(|e| e.to_string())?
+                .build();
+            let activations = MessageIterator::for_match_rule(rule, &self.conn, None)
+                .map_err(|e| format!("Failed to subscribe to shortcut activations: {}", e))?;
+
+            std::thread::spawn(move || {
+                for message in activations.flatten() {
+                    let Ok((session, id, _timestamp, _options)) = message.body()
+                        .deserialize::<(OwnedObjectPath, String, u64, HashMap<String, OwnedValue>)>()
+                    else {
+                        continue;
+                    };
+
+                    if id == SHORTCUT_ID {
+                        on_activated(session.to_string());
+                    }
+                }
+            });
+
+            Ok(())
+        }
+
+        /// Creates a session and asks the compositor to bind `hotkey` to it.
+        ///
+        /// The compositor may show a dialog and is free to pick another trigger,
+        /// the one it settled on is in [`ShortcutSession::trigger`].
+        pub fn bind(&self, hotkey: &Hotkey) -> Result<ShortcutSession, String> {
+            let token = new_token();
+            let options = HashMap::from([
+                ("handle_token", Value::from(token.as_str())),
+                ("session_handle_token", Value::from(token.as_str())),
+            ]);
+
+            let mut results = request(&self.conn, "org.freedesktop.portal.GlobalShortcuts", "CreateSession", (options,), &token, CREATE_SESSION_TIMEOUT)?;
+
+            // Older portals send the handle as a string, newer ones as an object path
+            let path = match results.remove("session_handle").map(Value::from) {
+                Some(Value::Str(path)) => path.to_string(),
+                Some(Value::ObjectPath(path)) => path.to_string(),
+                _ => return Err("Portal response is missing 'session_handle'".to_string()),
+            };
+            let path = OwnedObjectPath::try_from(path).map_err(|e| e.to_string())?;
+
+            let mut session = ShortcutSession {
+                conn: self.conn.clone(),
+                path,
+                trigger: None,
+            };
+
+            let shortcut = HashMap::from([
+                ("description", Value::from("Pick a color")),
+                ("preferred_trigger", Value::from(trigger(hotkey))),
+            ]);
+            let token = new_token();
+            let options = HashMap::from([("handle_token", Value::from(token.as_str()))]);
+
+            let mut results = request(
+                &self.conn,
+                "org.freedesktop.portal.GlobalShortcuts",
+                "BindShortcuts",
+                (&session.path, vec![(SHORTCUT_ID, shortcut)], "", options),
+                &token,
+                BIND_SHORTCUTS_TIMEOUT,
+            )?;
+
+            let bound: Vec<(String, HashMap<String, OwnedValue>)> = take(&mut results, "shortcuts").unwrap_or_default();
+            let Some((_, mut properties)) = bound.into_iter().find(|(id, _)| id == SHORTCUT_ID) else {
+                return Err("Compositor did not bind the shortcut".to_string());
+            };
+
+            session.trigger = take(&mut properties, "trigger_description").ok();
+            Ok(session)
+        }
+    }
+
+    /// A bound GlobalShortcuts session, closed again when dropped
+    pub struct ShortcutSession {
+        conn: Connection,
+        path: OwnedObjectPath,
+        trigger: Option<String>,
+    }
+
+    impl ShortcutSession {
+        pub fn handle(&self) -> &str {
+            self.path.as_str()
+        }
+
+        /// Human readable trigger the compositor assigned, if it told us
+        pub fn trigger(&self) -> Option<&str> {
+            self.trigger.as_deref()
+        }
+    }
+
+    impl Drop for ShortcutSession {
+        fn drop(&mut self) {
+            self.conn
+                .call_method(Some(DESTINATION), &self.path, Some("org.freedesktop.portal.Session"), "Close", &())
+                .ok();
+        }
+    }
+
+    /// Trigger in the XDG shortcuts format, e.g. `LOGO+SHIFT+a`
+    fn trigger(hotkey: &Hotkey) -> String {
+        use global_hotkey::hotkey::Modifiers;
+
+        let mut parts = Vec::new();
+        if hotkey.modifiers.contains(Modifiers::CONTROL) {
+            parts.push("CTRL".to_string());
+        }
+        if hotkey.modifiers.contains(Modifiers::ALT) {
+            parts.push("ALT".to_string());
+        }
+        if hotkey.modifiers.contains(Modifiers::SHIFT) {
+            parts.push("SHIFT".to_string());
+        }
+        if hotkey.modifiers.contains(Modifiers::SUPER) {
+            parts.push("LOGO".to_string());
+        }
+        parts.push(crate::diagnostics::x11_keysym_name(hotkey.code).unwrap_or_else(|| crate::hotkey::key_name(hotkey.code)));
+
+        parts.join("+")
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::path::PathBuf;
+        use std::sync::{Arc, Mutex};
+        use zbus::{fdo, interface};
+
+        use crate::testing::peer_connection;
+
+        /// Answers every request with `response` after writing a screenshot
+        /// to `screenshot`, or not at all when `response` is `None`
+        struct Portal {
+            response: Option<u32>,
+            screenshot: PathBuf,
+            closed: Arc<Mutex<Vec<String>>>,
+        }
+
+        /// The object at a request's handle, only here to be closed
+        struct PortalRequest {
+            handle: String,
+            closed: Arc<Mutex<Vec<String>>>,
+        }
+
+        #[interface(name = "org.freedesktop.portal.Request")]
+        impl PortalRequest {
+            fn close(&self) {
+                self.closed.lock().unwrap().push(self.handle.clone());
+            }
+        }
+
+        impl Portal {
+            async fn respond(&self, conn: &zbus::Connection, options: &Results, results: HashMap<&str, Value<'_>>) -> fdo::Result<OwnedObjectPath> {
+                let token = options.get("handle_token")
+                    .and_then(|value| String::try_from(value.try_clone().ok()?).ok())
+                    .unwrap_or_default();
+                let handle = format!("{}/request/peer/{}", PATH, token);
+                let path = OwnedObjectPath::try_from(handle.clone()).map_err(|e| fdo::Error::Failed(e.to_string()))?;
+
+                let request = PortalRequest { handle, closed: self.closed.clone() };
+                conn.object_server().at(&path, request).await?;
+
+                if let Some(response) = self.response {
+                    conn.emit_signal(None::<()>, &path, "org.freedesktop.portal.Request", "Response", &(response, results)).await?;
+                }
+                Ok(path)
+            }
+        }
+
+        #[interface(name = "org.freedesktop.portal.Screenshot")]
+        impl Portal {
+            async fn screenshot(&self, #[zbus(connection)] conn: &zbus::Connection, _parent_window: &str, options: Results) -> fdo::Result<OwnedObjectPath> {
+                RgbaImage::from_pixel(2, 2, image::Rgba([1, 2, 3, 255]))
+                    .save(&self.screenshot)
+                    .map_err(|e| fdo::Error::Failed(e.to_string()))?;
+                let uri = url::Url::from_file_path(&self.screenshot).unwrap().to_string();
+                self.respond(conn, &options, HashMap::from([("uri", Value::from(uri))])).await
+            }
+
+            async fn pick_color(&self, #[zbus(connection)] conn: &zbus::Connection, _parent_window: &str, options: Results) -> fdo::Result<OwnedObjectPath> {
+                self.respond(conn, &options, HashMap::from([("color", Value::from((0.2, 0.4, 0.6)))])).await
+            }
+        }
+
+        fn connect(response: Option<u32>, screenshot: PathBuf) -> (Connection, Connection, Arc<Mutex<Vec<String>>>) {
+            let closed = Arc::new(Mutex::new(Vec::new()));
+            let portal = Portal { response, screenshot, closed: closed.clone() };
+            let (conn, peer) = peer_connection(move |builder| builder.serve_at(PATH, portal));
+            (conn, peer, closed)
+        }
+
+        fn temp_screenshot(name: &str) -> PathBuf {
+            std::env::temp_dir().join(format!("yoinkctl-test-{}-{}.png", std::process::id(), name))
+        }
+
+        #[test]
+        fn successful_response_returns_its_results() {
+            let (conn, _peer, closed) = connect(Some(0), temp_screenshot("unused"));
+
+            assert_eq!(pick_color_on(&conn), Ok((51, 102, 153)));
+            assert!(closed.lock().unwrap().is_empty());
+        }
+
+        #[test]
+        fn cancelled_and_denied_responses_are_errors() {
+            let (conn, _peer, _) = connect(Some(1), temp_screenshot("unused"));
+            assert_eq!(pick_color_on(&conn), Err("Cancelled".to_string()));
+
+            let (conn, _peer, _) = connect(Some(2), temp_screenshot("unused"));
+            assert_eq!(pick_color_on(&conn), Err("org.freedesktop.portal.Screenshot.PickColor was denied".to_string()));
+        }
+
+        #[test]
+        fn unanswered_request_times_out_and_is_closed() {
+            let (conn, _peer, closed) = connect(None, temp_screenshot("unused"));
+            let token = new_token();
+            let options = HashMap::from([("handle_token", Value::from(token.as_str()))]);
+
+            let result = request(&conn, "org.freedesktop.portal.Screenshot", "PickColor", ("", options), &token, Duration::from_millis(200));
+            assert_eq!(result.unwrap_err(), "org.freedesktop.portal.Screenshot.PickColor did not answer within 200ms");
+            assert_eq!(*closed.lock().unwrap(), [format!("{}/request/peer/{}", PATH, token)]);
+        }
+
+        #[test]
+        fn only_temporary_screenshots_are_deleted() {
+            let temporary = temp_screenshot("portal");
+            let (conn, _peer, _) = connect(Some(0), temporary.clone());
+            assert_eq!(screenshot_on(&conn).map(|image| image.dimensions()), Ok((2, 2)));
+            assert!(!temporary.exists());
+
+            // Stands in for ~/Pictures, anywhere outside the temporary directories
+            let kept = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target").join(format!("yoinkctl-test-{}.png", std::process::id()));
+            assert!(!is_temporary(&kept));
+            let (conn, _peer, _) = connect(Some(0), kept.clone());
+            let loaded = screenshot_on(&conn);
+            let existed = kept.exists();
+            std::fs::remove_file(&kept).ok();
+            assert!(loaded.is_ok());
+            assert!(existed);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use image::RgbaImage;
+    use crate::hotkey::Hotkey;
+
+    const UNSUPPORTED: &str = "xdg-desktop-portal is only available on Linux";
+
+    pub fn interface_version(_interface: &str) -> Result<u32, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn screenshot() -> Result<RgbaImage, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn pick_color() -> Result<(u8, u8, u8), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub struct GlobalShortcuts;
+
+    impl GlobalShortcuts {
+        pub fn connect() -> Result<Self, String> {
+            Err(UNSUPPORTED.to_string())
+        }
+
+        pub fn listen<F>(&self, _on_activated: F) -> Result<(), String>
+        where
+            F: Fn(String) + Send + 'static,
+        {
+            Err(UNSUPPORTED.to_string())
+        }
+
+        pub fn bind(&self, _hotkey: &Hotkey) -> Result<ShortcutSession, String> {
+            Err(UNSUPPORTED.to_string())
+        }
+    }
+
+    pub struct ShortcutSession;
+
+    impl ShortcutSession {
+        pub fn handle(&self) -> &str {
+            ""
+        }
+
+        pub fn trigger(&self) -> Option<&str> {
+            None
+        }
+    }
+}
+
+pub use imp::{GlobalShortcuts, ShortcutSession};
+
+/// The `version` property of a portal interface, fails when the portal doesn't implement it
+pub fn interface_version(interface: &str) -> Result<u32, String> {
+    imp::interface_version(interface)
+}
+
+/// Full-desktop screenshot through `org.freedesktop.portal.Screenshot`
+pub fn screenshot() -> Result<RgbaImage, String> {
+    imp::screenshot()
+}
+
+/// Lets the compositor's own picker choose a color, used when a screenshot isn't allowed
+pub fn pick_color() -> Result<(u8, u8, u8), String> {
+    imp::pick_color()
+}
@@ -0,0 +1,226 @@
+use eframe::egui;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, OnceLock};
+use std::time::Instant;
+
+use crate::config::Config;
+use crate::lock::{self, LockOutcome, PickerLock};
+use crate::capture::{capture_screen, Capture};
+use crate::picker::{self, ColorPicker};
+use crate::portal;
+
+enum PickerCommand {
+    Show {
+        requested: Instant,
+        capture: Option<Capture>,
+    },
+    Quit,
+}
+
+/// Creates the resident picker, split into the handle the daemon keeps and
+/// the part that has to run on the main thread
+pub fn channel() -> (PickerHandle, ResidentPickerStarter) {
+    let (sender, receiver) = mpsc::channel();
+    let ctx = Arc::new(OnceLock::new());
+
+    (
+        PickerHandle { commands: sender, ctx: ctx.clone() },
+        ResidentPickerStarter { commands: receiver, ctx },
+    )
+}
+
+/// The daemon's side of the resident picker: sends commands and wakes the
+/// (otherwise idle) event loop so they get processed right away.
+#[derive(Clone)]
+pub struct PickerHandle {
+    commands: mpsc::Sender<PickerCommand>,
+    ctx: Arc<OnceLock<egui::Context>>,
+}
+
+impl PickerHandle {
+    /// Captures the screen and shows the pre-warmed picker on it.
+    ///
+    /// Fails once the picker window is gone, so the caller can fall back to
+    /// spawning a `yoinkctl pick` process.
+    pub fn show(&self, requested: Instant) -> Result<(), String> {
+        let capture = capture_screen();
+
+        // Without a screenshot the overlay is useless, let the compositor pick instead
+        if capture.is_none() && portal::should_use() {
+            std::thread::spawn(picker::pick_with_portal);
+            return Ok(());
+        }
+
+        self.commands
+            .send(PickerCommand::Show { requested, capture })
+            .map_err(|_| "Resident picker is not running".to_string())?;
+        self.wake();
+        Ok(())
+    }
+
+    pub fn quit(&self) {
+        self.commands.send(PickerCommand::Quit).ok();
+        self.wake();
+    }
+
+    fn wake(&self) {
+        if let Some(ctx) = self.ctx.get() {
+            ctx.request_repaint();
+        }
+    }
+}
+
+/// A picker window created once at daemon start and kept hidden between picks,
+/// so a hotkey press doesn't pay for eframe, GPU and font initialization.
+pub struct ResidentPicker {
+    picker: ColorPicker,
+    commands: mpsc::Receiver<PickerCommand>,
+    visible: bool,
+    hidden_after_start: bool,
+    shown_at: Option<Instant>,
+    /// The picker lock, held while the window is visible so `yoinkctl pick`
+    /// processes and the resident picker never overlap
+    lock: Option<PickerLock>,
+    /// Set by SIGUSR1 from a picker process started while we're visible
+    cancel_requested: Arc<AtomicBool>,
+}
+
+impl ResidentPicker {
+    fn hide(&mut self, ctx: &egui::Context) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        self.visible = false;
+        self.lock = None;
+    }
+
+    /// Takes the picker lock the way `yoinkctl pick` does. Fails when a picker
+    /// process holds it, after asking that one to close.
+    fn acquire_lock(&mut self) -> bool {
+        match lock::acquire() {
+            Ok(LockOutcome::Acquired(lock)) => {
+                self.lock = Some(lock);
+                true
+            }
+            Ok(LockOutcome::HeldBy(Some(pid))) => {
+                if let Err(e) = lock::cancel_running_picker(pid) {
+                    eprintln!("⚠️  {}", e);
+                }
+                false
+            }
+            Ok(LockOutcome::HeldBy(None)) => {
+                eprintln!("⚠️  Another picker holds the lock but hasn't written its PID, ignoring this press");
+                false
+            }
+            Err(e) => {
+                eprintln!("⚠️  {}, showing without single-instance lock", e);
+                true
+            }
+        }
+    }
+}
+
+/// Everything needed to run the resident picker on the main thread
+pub struct ResidentPickerStarter {
+    commands: mpsc::Receiver<PickerCommand>,
+    ctx: Arc<OnceLock<egui::Context>>,
+}
+
+impl ResidentPickerStarter {
+    /// Runs the picker's event loop until it receives [`PickerHandle::quit`]
+    pub fn run(self) -> Result<(), eframe::Error> {
+        let options = eframe::NativeOptions {
+            viewport: egui::ViewportBuilder::default()
+                .with_decorations(false)
+                .with_transparent(true)
+                .with_always_on_top()
+                .with_mouse_passthrough(false)
+                .with_taskbar(false)
+                // eframe shows the window after its first frame, keep that
+                // frame tiny and transparent before hiding it
+                .with_inner_size([1.0, 1.0])
+                .with_active(false),
+            centered: false,
+            hardware_acceleration: eframe::HardwareAcceleration::Preferred,
+            ..Default::default()
+        };
+
+        eframe::run_native(
+            "yoinkctl Picker",
+            options,
+            Box::new(move |cc| {
+                self.ctx.set(cc.egui_ctx.clone()).ok();
+
+                let mut picker = ColorPicker::new_with_config(cc, None, Config::load().unwrap_or_default());
+
+                let cancel_requested = Arc::new(AtomicBool::new(false));
+                #[cfg(unix)]
+                signal_hook::flag::register(signal_hook::consts::SIGUSR1, cancel_requested.clone()).ok();
+                picker.set_cancel_flag(cancel_requested.clone());
+
+                Ok(Box::new(ResidentPicker {
+                    picker,
+                    commands: self.commands,
+                    visible: false,
+                    hidden_after_start: false,
+                    shown_at: None,
+                    lock: None,
+                    cancel_requested,
+                }))
+            }),
+        )
+    }
+}
+
+impl eframe::App for ResidentPicker {
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::TRANSPARENT.to_array()
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if !self.hidden_after_start {
+            self.hide(ctx);
+            self.hidden_after_start = true;
+        }
+
+        while let Ok(command) = self.commands.try_recv() {
+            match command {
+                PickerCommand::Show { requested, capture } => {
+                    // A second hotkey press closes the open picker
+                    if self.visible {
+                        self.picker.cancel();
+                        continue;
+                    }
+
+                    if !self.acquire_lock() {
+                        continue;
+                    }
+                    // Left over from a cancel that arrived while hidden
+                    self.cancel_requested.store(false, Ordering::SeqCst);
+
+                    self.picker.reset(capture, Config::load().unwrap_or_default());
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(true));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                    self.visible = true;
+                    self.shown_at = Some(requested);
+                }
+                PickerCommand::Quit => {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    return;
+                }
+            }
+        }
+
+        // Stay idle while hidden, PickerHandle wakes us up
+        if !self.visible {
+            return;
+        }
+
+        if let Some(requested) = self.shown_at.take() {
+            println!("⏱ Picker frame ready {:.1} ms after hotkey", requested.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        if self.picker.run_frame(ctx) {
+            self.hide(ctx);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,27 @@
+//! Helpers shared by tests
+
+use std::os::unix::net::UnixStream;
+use zbus::blocking::connection::Builder;
+use zbus::blocking::Connection;
+use zbus::Guid;
+
+/// A client connection to a stand-in D-Bus service, peer to peer instead of
+/// through a bus. `serve` registers the service's objects on its end, which
+/// is returned too and has to stay alive for the test.
+pub fn peer_connection<F>(serve: F) -> (Connection, Connection)
+where
+    F: FnOnce(Builder<'static>) -> zbus::Result<Builder<'static>> + Send + 'static,
+{
+    let (client, peer) = UnixStream::pair().unwrap();
+    let guid = Guid::generate();
+
+    // Both ends wait for each other's handshake
+    let peer = std::thread::spawn(move || {
+        Builder::unix_stream(peer)
+            .server(guid)
+            .and_then(|builder| serve(builder.p2p()))
+            .and_then(|builder| builder.build())
+    });
+    let client = Builder::unix_stream(client).p2p().build().unwrap();
+    (client, peer.join().unwrap().unwrap())
+}
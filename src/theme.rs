@@ -0,0 +1,167 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::color::Oklch;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeMode {
+    /// Follow the desktop's light or dark preference
+    System,
+    #[default]
+    Dark,
+    Light,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 3] = [ThemeMode::System, ThemeMode::Dark, ThemeMode::Light];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemeMode::System => "System",
+            ThemeMode::Dark => "Dark",
+            ThemeMode::Light => "Light",
+        }
+    }
+
+    fn preference(self) -> egui::ThemePreference {
+        match self {
+            ThemeMode::System => egui::ThemePreference::System,
+            ThemeMode::Dark => egui::ThemePreference::Dark,
+            ThemeMode::Light => egui::ThemePreference::Light,
+        }
+    }
+}
+
+/// Look of the config app and the picker overlay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub mode: ThemeMode,
+    pub accent: (u8, u8, u8),
+    pub crosshair_color: (u8, u8, u8),
+    /// Length of each crosshair arm in points
+    pub crosshair_size: f32,
+    /// Multiplies text size in the config app and the picker's info box
+    pub font_scale: f32,
+    /// No smoothing or animations: the magnifier sticks to the cursor
+    pub reduced_motion: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            mode: ThemeMode::default(),
+            accent: (100, 255, 100),
+            crosshair_color: (255, 255, 255),
+            crosshair_size: 20.0,
+            font_scale: 1.0,
+            reduced_motion: false,
+        }
+    }
+}
+
+impl Theme {
+    /// Whether to draw in dark colors, resolving `System` through egui
+    pub fn is_dark(&self, ctx: &egui::Context) -> bool {
+        match self.mode {
+            ThemeMode::Dark => true,
+            ThemeMode::Light => false,
+            ThemeMode::System => ctx.system_theme().unwrap_or(egui::Theme::Dark) == egui::Theme::Dark,
+        }
+    }
+
+    /// Sets up egui's visuals, zoom and animations for the config app
+    pub fn apply(&self, ctx: &egui::Context) {
+        ctx.set_theme(self.mode.preference());
+
+        for (theme, dark) in [(egui::Theme::Dark, true), (egui::Theme::Light, false)] {
+            let palette = Palette::new(self, dark);
+            let mut visuals = if dark { egui::Visuals::dark() } else { egui::Visuals::light() };
+            visuals.window_rounding = egui::Rounding::same(0.0);
+            visuals.panel_fill = palette.background;
+            visuals.selection.bg_fill = palette.accent.gamma_multiply(0.6);
+            visuals.hyperlink_color = palette.accent;
+            ctx.set_visuals_of(theme, visuals);
+        }
+
+        ctx.set_zoom_factor(self.font_scale.clamp(0.5, 3.0));
+        ctx.style_mut(|style| {
+            style.animation_time = if self.reduced_motion { 0.0 } else { egui::Style::default().animation_time };
+        });
+    }
+}
+
+/// Concrete colors for one theme
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub background: egui::Color32,
+    pub card: egui::Color32,
+    /// Sunken areas like the history list, and its alternate rows
+    pub inset: egui::Color32,
+    pub inset_alt: egui::Color32,
+    pub border: egui::Color32,
+    pub divider: egui::Color32,
+    pub text: egui::Color32,
+    pub muted: egui::Color32,
+    pub faint: egui::Color32,
+    /// Hex codes in the history
+    pub code: egui::Color32,
+    /// The theme accent, darkened on light backgrounds so it stays readable
+    pub accent: egui::Color32,
+    /// Picker overlay boxes and their text
+    pub overlay: egui::Color32,
+    pub overlay_text: egui::Color32,
+    pub overlay_muted: egui::Color32,
+    pub crosshair: egui::Color32,
+}
+
+impl Palette {
+    pub fn new(theme: &Theme, dark: bool) -> Self {
+        let rgb = |(r, g, b): (u8, u8, u8)| egui::Color32::from_rgb(r, g, b);
+        let accent = if dark {
+            theme.accent
+        } else {
+            let lch = Oklch::from_rgb(theme.accent);
+            Oklch { l: lch.l.min(0.55), ..lch }.to_rgb()
+        };
+
+        if dark {
+            Self {
+                background: egui::Color32::from_rgb(18, 18, 20),
+                card: egui::Color32::from_rgb(28, 28, 32),
+                inset: egui::Color32::from_rgb(12, 12, 14),
+                inset_alt: egui::Color32::from_rgb(16, 16, 18),
+                border: egui::Color32::from_rgb(40, 40, 44),
+                divider: egui::Color32::from_gray(60),
+                text: egui::Color32::from_gray(180),
+                muted: egui::Color32::from_gray(140),
+                faint: egui::Color32::from_gray(100),
+                code: egui::Color32::from_rgb(200, 200, 255),
+                accent: rgb(accent),
+                overlay: egui::Color32::from_black_alpha(200),
+                overlay_text: egui::Color32::WHITE,
+                overlay_muted: egui::Color32::from_gray(150),
+                crosshair: rgb(theme.crosshair_color),
+            }
+        } else {
+            Self {
+                background: egui::Color32::from_rgb(243, 243, 245),
+                card: egui::Color32::WHITE,
+                inset: egui::Color32::from_rgb(250, 250, 251),
+                inset_alt: egui::Color32::from_rgb(240, 240, 243),
+                border: egui::Color32::from_rgb(215, 215, 220),
+                divider: egui::Color32::from_gray(200),
+                text: egui::Color32::from_gray(60),
+                muted: egui::Color32::from_gray(100),
+                faint: egui::Color32::from_gray(140),
+                code: egui::Color32::from_rgb(40, 40, 130),
+                accent: rgb(accent),
+                overlay: egui::Color32::from_white_alpha(230),
+                overlay_text: egui::Color32::from_gray(20),
+                overlay_muted: egui::Color32::from_gray(90),
+                crosshair: rgb(theme.crosshair_color),
+            }
+        }
+    }
+}